@@ -0,0 +1,15 @@
+//! Diagnostic example that checks every layer from USB context to measurement
+//!
+//! Run this and attach the output when reporting a connection issue.
+
+use adcmt_7351_controller::DiagnosticReport;
+
+fn main() {
+    let report = DiagnosticReport::run();
+    print!("{}", report);
+
+    if let Some((step, _)) = report.first_failure() {
+        eprintln!("Diagnosis failed at: {}", step.name());
+        std::process::exit(1);
+    }
+}
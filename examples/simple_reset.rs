@@ -15,13 +15,13 @@ fn main() -> Result<()> {
     let mut device = Device::open(&device_metadata)?;
     println!("Device opened successfully");
 
-    // Send *RST command to reset the device
-    device.write("*RST")?;
-    println!("Sent *RST command");
+    // Send *RST command and wait until the device responds again
+    device.reset()?;
+    println!("Device reset");
 
-    // Receive response from the device
-    let response = device.read()?;
-    println!("Received response: {}", response);
+    // Query the function the device came back with
+    let function = device.function()?;
+    println!("Function after reset: {:?}", function);
 
     println!("Done!");
     Ok(())
@@ -0,0 +1,23 @@
+//! Example that measures with the simulated instrument, without any hardware attached
+
+use adcmt_7351_controller::{Device, ResistanceRange, SamplingRate, ShortHand, SimulatedDevice};
+use anyhow::Result;
+
+fn main() -> Result<()> {
+    // Open a device over the simulated transport
+    let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+    println!("Identity: {}", device.identify()?);
+
+    // Configure like a real instrument
+    device.sampling_rate_set(SamplingRate::SLOW1)?;
+    println!("Sampling rate: {:?}", device.sampling_rate()?);
+
+    // Take a few readings
+    for _ in 0..3 {
+        let reading = device.measure(ShortHand::Resistance(ResistanceRange::R20k))?;
+        println!("Reading: {}", reading);
+    }
+
+    println!("Done!");
+    Ok(())
+}
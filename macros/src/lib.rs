@@ -0,0 +1,197 @@
+//! Procedural macros for defining ADCMT 7351A instrument commands
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{Ident, ItemEnum, LitStr, Type, parse_macro_input};
+
+/// Arguments of `#[adc_command(...)]`
+struct CommandArgs {
+    query: LitStr,
+    set: LitStr,
+    response: Type,
+}
+
+/// Generate the query/set method pair on `Device` for a typed setting enum
+///
+/// ```ignore
+/// #[adc_command(query = "PR?", set = "PR{}", response = u8)]
+/// #[derive(Debug, FromPrimitive, Clone, PartialEq)]
+/// pub enum SamplingRate { ... }
+/// ```
+///
+/// generates `Device::sampling_rate()` sending `PR?` and parsing the response into
+/// `SamplingRate` with the parser registered for `PR` in `ResponseParsers`, and
+/// `Device::sampling_rate_set()` sending `PR<code>` (the code cast to `u8`) and verifying the
+/// result. Method names are the snake case of the enum name.
+///
+/// A `SamplingRate::COMMAND` description is generated as well, for the command reference.
+///
+/// The generated code is an inherent impl of `crate::Device`, so the macro is only usable
+/// inside the controller crate.
+#[proc_macro_attribute]
+pub fn adc_command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut query = None;
+    let mut set = None;
+    let mut response = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("query") {
+            query = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("set") {
+            set = Some(meta.value()?.parse::<LitStr>()?);
+        } else if meta.path.is_ident("response") {
+            response = Some(meta.value()?.parse::<Type>()?);
+        } else {
+            return Err(meta.error("expected `query`, `set` or `response`"));
+        }
+        Ok(())
+    });
+    parse_macro_input!(attr with parser);
+
+    let item_enum = parse_macro_input!(item as ItemEnum);
+
+    let args = match (query, set, response) {
+        (Some(query), Some(set), Some(response)) => CommandArgs {
+            query,
+            set,
+            response,
+        },
+        _ => {
+            return syn::Error::new(
+                Span::call_site(),
+                "`adc_command` requires `query`, `set` and `response`",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    match expand(&item_enum, &args) {
+        Ok(methods) => quote! {
+            #item_enum
+            #methods
+        }
+        .into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Generate the method pair for an enum
+fn expand(item_enum: &ItemEnum, args: &CommandArgs) -> syn::Result<proc_macro2::TokenStream> {
+    let query = args.query.value();
+    let set = args.set.value();
+    let response = &args.response;
+
+    query
+        .strip_suffix('?')
+        .ok_or_else(|| syn::Error::new(args.query.span(), "query command must end with `?`"))?;
+    if !set.contains("{}") {
+        return Err(syn::Error::new(
+            args.set.span(),
+            "set command must contain a `{}` placeholder",
+        ));
+    }
+
+    let enum_name = &item_enum.ident;
+    let words = split_words(&enum_name.to_string());
+    let snake = words.join("_");
+    let spaced = words.join(" ");
+    let label = capitalize(&spaced);
+
+    let getter = Ident::new(&snake, enum_name.span());
+    let setter = format_ident!("{}_set", getter);
+    let set_placeholder = set.replace("{}", &format!("<{}>", snake));
+
+    let getter_doc = format!("{}: get current {}", label, spaced);
+    let setter_doc = format!("{}: set current {}", label, spaced);
+    let query_doc = format!("ADC command: `{}`", query);
+    let set_doc = format!("ADC command: `{}`", set_placeholder);
+    let verify_error = format!("Failed to set {}", spaced);
+    type_suffix(response)?;
+    let enum_label = enum_name.to_string();
+    let variants = item_enum.variants.iter().map(|variant| &variant.ident);
+
+    Ok(quote! {
+        impl #enum_name {
+            /// Description of the query/set command pair of this setting
+            pub const COMMAND: crate::CommandDescription = crate::CommandDescription {
+                name: #snake,
+                description: #label,
+                query: Some(#query),
+                set: Some(#set),
+                parameter: crate::ParameterKind::Choice(&[
+                    #((stringify!(#variants), #enum_name::#variants as i64)),*
+                ]),
+                response: #enum_label,
+            };
+        }
+
+
+        impl crate::Device {
+            #[doc = #getter_doc]
+            #[doc = ""]
+            #[doc = #query_doc]
+            pub fn #getter(&mut self) -> anyhow::Result<#enum_name> {
+                self.query_parsed::<#enum_name>(#query)
+            }
+
+            #[doc = #setter_doc]
+            #[doc = ""]
+            #[doc = #set_doc]
+            pub fn #setter(&mut self, #getter: #enum_name) -> anyhow::Result<()> {
+                // Set the value
+                self.write(&format!(#set, #getter.clone() as #response))?;
+
+                // Verify the value
+                if self.#getter()? != #getter {
+                    return Err(anyhow::anyhow!(#verify_error));
+                }
+
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Split a `CamelCase` identifier into lowercase words
+fn split_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for c in name.chars() {
+        if c.is_uppercase() || words.is_empty() {
+            words.push(c.to_lowercase().collect());
+        } else if let Some(word) = words.last_mut() {
+            word.push(c);
+        }
+    }
+    words
+}
+
+/// Uppercase the first character
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Name of a primitive integer type, for the matching `FromPrimitive` method
+fn type_suffix(ty: &Type) -> syn::Result<String> {
+    if let Type::Path(path) = ty
+        && let Some(ident) = path.path.get_ident()
+    {
+        let name = ident.to_string();
+        if matches!(
+            name.as_str(),
+            "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64"
+        ) {
+            return Ok(name);
+        }
+    }
+
+    Err(syn::Error::new_spanned(
+        ty,
+        "response must be a primitive integer type",
+    ))
+}
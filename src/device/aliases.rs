@@ -0,0 +1,161 @@
+//! Persistent mapping from serial numbers to user aliases
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Ok, Result, anyhow};
+
+/// Alias file name inside the configuration directory
+const ALIASES_FILE_NAME: &str = "aliases";
+
+/// Mapping from user alias to device serial number
+///
+/// Persisted as a plain text file with one `alias = serial` entry per line. Empty lines and
+/// lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceAliases {
+    entries: BTreeMap<String, String>,
+}
+
+impl DeviceAliases {
+    /// Create an empty alias mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default location of the alias file
+    ///
+    /// `$XDG_CONFIG_HOME/adcmt-7351/aliases`, falling back to `$HOME/.config` and
+    /// `%APPDATA%` for the configuration directory.
+    pub fn default_path() -> Result<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .ok_or_else(|| anyhow!("Failed to locate configuration directory"))?;
+
+        Ok(config_dir.join("adcmt-7351").join(ALIASES_FILE_NAME))
+    }
+
+    /// Load aliases from the default location, empty if the file does not exist
+    pub fn load_default() -> Result<Self> {
+        let path = Self::default_path()?;
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        Self::load(&path)
+    }
+
+    /// Load aliases from the given file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read alias file '{}'", path.display()))?;
+
+        let mut entries = BTreeMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (alias, serial) = line.split_once('=').ok_or_else(|| {
+                anyhow!(
+                    "Invalid alias entry on line {} of '{}'",
+                    index + 1,
+                    path.display()
+                )
+            })?;
+
+            entries.insert(alias.trim().to_string(), serial.trim().to_string());
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Save aliases to the given file, creating parent directories if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create alias directory '{}'", parent.display())
+            })?;
+        }
+
+        let content: String = self
+            .entries
+            .iter()
+            .map(|(alias, serial)| format!("{} = {}\n", alias, serial))
+            .collect();
+
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write alias file '{}'", path.display()))
+    }
+
+    /// Save aliases to the default location
+    pub fn save_default(&self) -> Result<()> {
+        self.save(&Self::default_path()?)
+    }
+
+    /// Assign an alias to a serial number, replacing any previous assignment of the alias
+    ///
+    /// Surrounding whitespace is trimmed from both, as it is when loading the file.
+    pub fn set(&mut self, alias: &str, serial: &str) -> Result<()> {
+        let alias = alias.trim();
+        let serial = serial.trim();
+
+        if alias.is_empty() || alias.contains(['=', '\n', '\r']) || alias.starts_with('#') {
+            return Err(anyhow!("Invalid alias '{}'", alias));
+        }
+        if serial.is_empty() || serial.contains(['=', '\n', '\r']) {
+            return Err(anyhow!("Invalid serial number '{}'", serial));
+        }
+
+        self.entries.insert(alias.to_string(), serial.to_string());
+        Ok(())
+    }
+
+    /// Remove an alias, returning the serial number it pointed to
+    pub fn remove(&mut self, alias: &str) -> Option<String> {
+        self.entries.remove(alias)
+    }
+
+    /// Get the serial number for an alias
+    pub fn serial(&self, alias: &str) -> Option<&str> {
+        self.entries.get(alias).map(String::as_str)
+    }
+
+    /// Get the first alias assigned to a serial number
+    pub fn alias(&self, serial: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(_, s)| s.as_str() == serial)
+            .map(|(alias, _)| alias.as_str())
+    }
+
+    /// Iterate over all `(alias, serial)` entries
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(alias, serial)| (alias.as_str(), serial.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeviceAliases;
+
+    #[test]
+    fn set_trims_and_rejects_invalid_entries() {
+        let mut aliases = DeviceAliases::new();
+
+        aliases.set("  bench ", " 12345\t").unwrap();
+        assert_eq!(aliases.serial("bench"), Some("12345"));
+
+        assert!(aliases.set("a=b", "12345").is_err());
+        assert!(aliases.set("bench", "123\n45").is_err());
+        assert!(aliases.set("bench", "123=45").is_err());
+        assert!(aliases.set("bench", "  ").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Async device wrapper running blocking USB IO off the async runtime
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+
+use crate::{Device, FunctionCode, RawRange, Reading, ShortHand, TriggerSource, UsbDeviceMetadata};
+
+/// Async handle to a device
+///
+/// Every operation runs the blocking `Device` method on the tokio blocking thread pool,
+/// so USB transfers and the inter-command delays never block async worker threads.
+/// Clones share the same underlying device and their operations are serialized.
+#[derive(Clone)]
+pub struct AsyncDevice {
+    inner: Arc<Mutex<Device>>,
+}
+
+impl AsyncDevice {
+    /// Wrap an already opened device
+    pub fn new(device: Device) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(device)),
+        }
+    }
+
+    /// Open the device by the metadata
+    pub async fn open(metadata: &UsbDeviceMetadata) -> Result<Self> {
+        let metadata = metadata.clone();
+        let device = tokio::task::spawn_blocking(move || Device::open(&metadata))
+            .await
+            .map_err(|e| anyhow!("Blocking open task failed: {}", e))??;
+
+        Ok(Self::new(device))
+    }
+
+    /// Run a blocking operation on the device in the blocking thread pool
+    pub async fn run<T, F>(&self, operation: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Device) -> Result<T> + Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || {
+            let mut device = inner
+                .lock()
+                .map_err(|_| anyhow!("Device lock poisoned by a panicked operation"))?;
+            operation(&mut device)
+        })
+        .await
+        .map_err(|e| anyhow!("Blocking device task failed: {}", e))?
+    }
+
+    /// Unwrap the blocking device if no other handle shares it
+    pub fn into_inner(self) -> Result<Device> {
+        let mutex = Arc::try_unwrap(self.inner)
+            .map_err(|_| anyhow!("Device is still shared by other async handles"))?;
+        mutex
+            .into_inner()
+            .map_err(|_| anyhow!("Device lock poisoned by a panicked operation"))
+    }
+
+    /// Write a command to the device
+    pub async fn write(&self, command: &str) -> Result<()> {
+        let command = command.to_string();
+        self.run(move |device| device.write(&command)).await
+    }
+
+    /// Read a response from the device
+    pub async fn read(&self) -> Result<String> {
+        self.run(|device| device.read()).await
+    }
+
+    /// Write a query command and read its response
+    pub async fn query(&self, command: &str) -> Result<String> {
+        let command = command.to_string();
+        self.run(move |device| {
+            device.write(&command)?;
+            device.read()
+        })
+        .await
+    }
+
+    /// Clear the device
+    pub async fn clear(&self) -> Result<()> {
+        self.run(|device| device.clear()).await
+    }
+
+    /// Measurement function: get the current function code
+    pub async fn function(&self) -> Result<FunctionCode> {
+        self.run(|device| device.function()).await
+    }
+
+    /// Measurement function: set the function code
+    pub async fn function_set(&self, function_code: FunctionCode) -> Result<()> {
+        self.run(move |device| device.function_set(function_code))
+            .await
+    }
+
+    /// Measurement range: get the current raw range
+    pub async fn range(&self) -> Result<RawRange> {
+        self.run(|device| device.range()).await
+    }
+
+    /// Measurement range: set the raw range
+    pub async fn range_set(&self, raw_range: RawRange) -> Result<()> {
+        self.run(move |device| device.range_set(raw_range)).await
+    }
+
+    /// Shorthand: get the current function and range
+    pub async fn shorthand(&self) -> Result<ShortHand> {
+        self.run(|device| device.shorthand()).await
+    }
+
+    /// Shorthand: set the function and range
+    pub async fn shorthand_set(&self, shorthand: ShortHand) -> Result<()> {
+        self.run(move |device| device.shorthand_set(shorthand))
+            .await
+    }
+
+    /// Start: leave the IDLE state
+    pub async fn start(&self) -> Result<()> {
+        self.run(|device| device.start()).await
+    }
+
+    /// Abort: enter the IDLE state
+    pub async fn abort(&self) -> Result<()> {
+        self.run(|device| device.abort()).await
+    }
+
+    /// Trigger source: get the current trigger source
+    pub async fn trigger_source(&self) -> Result<TriggerSource> {
+        self.run(|device| device.trigger_source()).await
+    }
+
+    /// Trigger source: set the trigger source
+    pub async fn trigger_source_set(&self, trigger_source: TriggerSource) -> Result<()> {
+        self.run(move |device| device.trigger_source_set(trigger_source))
+            .await
+    }
+
+    /// Sampling count: get the current sampling count
+    pub async fn sampling_count(&self) -> Result<u16> {
+        self.run(|device| device.sampling_count()).await
+    }
+
+    /// Sampling count: set the sampling count
+    pub async fn sampling_count_set(&self, sampling_count: u16) -> Result<()> {
+        self.run(move |device| device.sampling_count_set(sampling_count))
+            .await
+    }
+
+    /// Fetch: read the pending measurement data as a typed reading
+    pub async fn fetch(&self) -> Result<Reading> {
+        self.run(|device| device.fetch()).await
+    }
+
+    /// Measure: configure, trigger a single conversion and fetch it
+    pub async fn measure(&self, shorthand: ShortHand) -> Result<Reading> {
+        self.run(move |device| device.measure(shorthand)).await
+    }
+}
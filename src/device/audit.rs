@@ -0,0 +1,98 @@
+//! Audit log of state-changing commands
+
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+/// Single audit log entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// When the command was issued
+    pub timestamp: SystemTime,
+
+    /// Caller-supplied tag active when the command was issued
+    pub tag: Option<String>,
+
+    /// The state-changing command
+    pub command: String,
+
+    /// `Ok` if the command was sent, otherwise the error message
+    pub result: std::result::Result<(), String>,
+}
+
+impl AuditEntry {
+    /// Format the entry as one tab-separated line:
+    /// `<unix seconds>.<millis>\t<tag or ->\t<command>\t<ok | error: message>`
+    pub fn to_line(&self) -> String {
+        let result = match &self.result {
+            Ok(()) => "ok".to_string(),
+            Err(e) => format!("error: {}", e.replace(['\t', '\n'], " ")),
+        };
+
+        format!(
+            "{}\t{}\t{}\t{}",
+            format_timestamp(self.timestamp),
+            self.tag.as_deref().unwrap_or("-"),
+            self.command,
+            result
+        )
+    }
+}
+
+/// Format a timestamp as `<unix seconds>.<millis>`
+pub(crate) fn format_timestamp(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format!(
+        "{}.{:03}",
+        since_epoch.as_secs(),
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Append-only audit log, kept in memory and optionally mirrored to a file
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+    file: Option<File>,
+}
+
+impl AuditLog {
+    /// Create an in-memory audit log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an audit log that also appends every entry to the given file
+    pub fn with_file(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log file '{}'", path.display()))?;
+
+        Ok(Self {
+            entries: Vec::new(),
+            file: Some(file),
+        })
+    }
+
+    /// Get all entries recorded so far
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Record an entry, appending it to the file sink if any
+    pub(crate) fn record(&mut self, entry: AuditEntry) -> Result<()> {
+        if let Some(file) = &mut self.file {
+            writeln!(file, "{}", entry.to_line()).context("Failed to append to audit log file")?;
+        }
+
+        self.entries.push(entry);
+        Ok(())
+    }
+}
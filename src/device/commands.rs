@@ -0,0 +1,229 @@
+//! Machine-readable reference of the typed command set
+
+use crate::{AutoZero, FunctionCode, NumberOfDisplayDigits, RawRange, SamplingRate, TriggerSource};
+
+/// Parameter accepted by the set form of a command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterKind {
+    /// No parameter
+    None,
+
+    /// One of the listed `(name, code)` choices
+    Choice(&'static [(&'static str, i64)]),
+
+    /// Integer within the inclusive bounds
+    Integer {
+        /// Lowest accepted value
+        min: i64,
+
+        /// Highest accepted value
+        max: i64,
+    },
+}
+
+/// Description of a typed command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandDescription {
+    /// Name of the getter method on `Device`, the setter has a `_set` suffix
+    pub name: &'static str,
+
+    /// Human-readable description
+    pub description: &'static str,
+
+    /// Query command string, if the setting can be queried
+    pub query: Option<&'static str>,
+
+    /// Set command string, `{}` stands for the parameter
+    pub set: Option<&'static str>,
+
+    /// Parameter of the set command
+    pub parameter: ParameterKind,
+
+    /// Type returned by the query
+    pub response: &'static str,
+}
+
+/// All typed commands of the crate
+static COMMANDS: &[CommandDescription] = &[
+    CommandDescription {
+        name: "function",
+        description: "Measurement function",
+        query: Some("F?"),
+        set: Some("F{}"),
+        parameter: ParameterKind::Choice(&[
+            ("DCV", FunctionCode::DCV as i64),
+            ("ACV", FunctionCode::ACV as i64),
+            ("Resistance", FunctionCode::Resistance as i64),
+            ("DCI", FunctionCode::DCI as i64),
+            ("ACI", FunctionCode::ACI as i64),
+            ("ACVCoupling", FunctionCode::ACVCoupling as i64),
+            ("ACICoupling", FunctionCode::ACICoupling as i64),
+            ("Diode", FunctionCode::Diode as i64),
+            (
+                "ResistanceLowPower",
+                FunctionCode::ResistanceLowPower as i64,
+            ),
+            ("Continuity", FunctionCode::Continuity as i64),
+            ("Frequency", FunctionCode::Frequency as i64),
+        ]),
+        response: "FunctionCode",
+    },
+    CommandDescription {
+        name: "range",
+        description: "Measurement range",
+        query: Some("R?"),
+        set: Some("R{}"),
+        parameter: ParameterKind::Choice(&[
+            ("AUTO", RawRange::AUTO as i64),
+            ("R3", RawRange::R3 as i64),
+            ("R4", RawRange::R4 as i64),
+            ("R5", RawRange::R5 as i64),
+            ("R6", RawRange::R6 as i64),
+            ("R7", RawRange::R7 as i64),
+            ("R8", RawRange::R8 as i64),
+            ("R9", RawRange::R9 as i64),
+        ]),
+        response: "RawRange",
+    },
+    CommandDescription {
+        name: "range_fix",
+        description: "Fix automatic range by switching to manual range",
+        query: None,
+        set: Some("RX"),
+        parameter: ParameterKind::None,
+        response: "()",
+    },
+    SamplingRate::COMMAND,
+    NumberOfDisplayDigits::COMMAND,
+    AutoZero::COMMAND,
+    CommandDescription {
+        name: "continuity_threshold_constant",
+        description: "Continuity threshold constant",
+        query: Some("KOM?"),
+        set: Some("KOM{}"),
+        parameter: ParameterKind::Integer {
+            min: u16::MIN as i64,
+            max: u16::MAX as i64,
+        },
+        response: "String",
+    },
+    CommandDescription {
+        name: "start",
+        description: "Leave the IDLE state",
+        query: None,
+        set: Some("INI"),
+        parameter: ParameterKind::None,
+        response: "()",
+    },
+    CommandDescription {
+        name: "abort",
+        description: "Enter the IDLE state",
+        query: None,
+        set: Some("ABO"),
+        parameter: ParameterKind::None,
+        response: "()",
+    },
+    CommandDescription {
+        name: "continuously_measure",
+        description: "Continuous measurement",
+        query: Some("INIC?"),
+        set: Some("INIC{}"),
+        parameter: ParameterKind::Choice(&[("Disable", 0), ("Enable", 1)]),
+        response: "bool",
+    },
+    TriggerSource::COMMAND,
+    CommandDescription {
+        name: "trigger_delay",
+        description: "Trigger delay",
+        query: Some("TRD?"),
+        set: Some("TRD{}"),
+        parameter: ParameterKind::Integer {
+            min: u16::MIN as i64,
+            max: u16::MAX as i64,
+        },
+        response: "String",
+    },
+    CommandDescription {
+        name: "sampling_count",
+        description: "Sampling count",
+        query: Some("SPN?"),
+        set: Some("SPN{}"),
+        parameter: ParameterKind::Integer {
+            min: u16::MIN as i64,
+            max: u16::MAX as i64,
+        },
+        response: "u16",
+    },
+    CommandDescription {
+        name: "service_request_enable",
+        description: "Status bits raising a service request",
+        query: Some("*SRE?"),
+        set: Some("*SRE{}"),
+        parameter: ParameterKind::Integer {
+            min: u8::MIN as i64,
+            max: u8::MAX as i64,
+        },
+        response: "StatusByte",
+    },
+];
+
+/// Get the description of every typed command
+pub fn command_reference() -> &'static [CommandDescription] {
+    COMMANDS
+}
+
+/// Get the command reference as a JSON array
+///
+/// Each entry has `name`, `description`, `query`, `set` (`null` when absent), `response`
+/// and `parameter`, which is `null`, `{"choices": [{"name", "code"}]}` or
+/// `{"min", "max"}`.
+pub fn command_reference_json() -> String {
+    let entries: Vec<String> = COMMANDS
+        .iter()
+        .map(|command| {
+            let parameter = match command.parameter {
+                ParameterKind::None => "null".to_string(),
+                ParameterKind::Choice(choices) => {
+                    let choices: Vec<String> = choices
+                        .iter()
+                        .map(|(name, code)| {
+                            format!("{{\"name\":{},\"code\":{}}}", json_string(name), code)
+                        })
+                        .collect();
+                    format!("{{\"choices\":[{}]}}", choices.join(","))
+                }
+                ParameterKind::Integer { min, max } => {
+                    format!("{{\"min\":{},\"max\":{}}}", min, max)
+                }
+            };
+
+            format!(
+                "{{\"name\":{},\"description\":{},\"query\":{},\"set\":{},\"response\":{},\"parameter\":{}}}",
+                json_string(command.name),
+                json_string(command.description),
+                command.query.map_or("null".to_string(), json_string),
+                command.set.map_or("null".to_string(), json_string),
+                json_string(command.response),
+                parameter
+            )
+        })
+        .collect();
+
+    format!("[{}]", entries.join(","))
+}
+
+/// Internal function: Quote and escape a string for JSON
+pub(crate) fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,192 @@
+//! Layer-by-layer connection diagnostics
+
+use std::fmt;
+
+use anyhow::Result;
+
+use crate::{Device, DeviceManager};
+
+/// Diagnostic steps, ordered from the lowest layer to the highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStep {
+    /// Create the USB host context
+    Context,
+
+    /// Enumerate ADCMT 7351 devices on the bus
+    Enumerate,
+
+    /// Open the device, claim the interface and send the init control transfers
+    Open,
+
+    /// Query the identity with `*IDN?`
+    Identify,
+
+    /// Read the status byte via control transfer
+    Status,
+
+    /// Take a sample measurement
+    Measure,
+}
+
+impl DiagnosticStep {
+    /// Human-readable name of the step
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Context => "USB context",
+            Self::Enumerate => "Enumeration",
+            Self::Open => "Open and init transfers",
+            Self::Identify => "Identification (*IDN?)",
+            Self::Status => "Status byte read",
+            Self::Measure => "Sample measurement",
+        }
+    }
+}
+
+/// Outcome of a single diagnostic step
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticOutcome {
+    /// Step succeeded, with details
+    Passed(String),
+
+    /// Step failed, with the full error chain
+    Failed(String),
+
+    /// Step was not run because a lower layer failed
+    Skipped,
+}
+
+/// Report of a diagnostic run
+#[derive(Debug, Clone)]
+pub struct DiagnosticReport {
+    /// Outcome of every step, in execution order
+    pub steps: Vec<(DiagnosticStep, DiagnosticOutcome)>,
+}
+
+impl DiagnosticReport {
+    /// Run all diagnostic steps against the first available device
+    ///
+    /// Steps stop at the first failure; the remaining steps are reported as skipped.
+    pub fn run() -> Self {
+        let mut report = Self { steps: Vec::new() };
+
+        let Some(manager) = report.step(DiagnosticStep::Context, || {
+            DeviceManager::new().map(|manager| (manager, "created".to_string()))
+        }) else {
+            return report.skip_rest();
+        };
+
+        let Some(metadata) = report.step(DiagnosticStep::Enumerate, || {
+            let devices = manager.list_devices()?;
+            let first = devices
+                .first()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No ADCMT 7351 device found"))?;
+            let details = format!(
+                "{} device(s) found, using serial {}",
+                devices.len(),
+                first.serial_number.as_deref().unwrap_or("<unknown>")
+            );
+            Ok((first, details))
+        }) else {
+            return report.skip_rest();
+        };
+
+        let Some(mut device) = report.step(DiagnosticStep::Open, || {
+            Device::open(&metadata).map(|device| (device, "opened".to_string()))
+        }) else {
+            return report.skip_rest();
+        };
+
+        let identified = report.step(DiagnosticStep::Identify, || {
+            device.identify().map(|info| ((), info.to_string()))
+        });
+        if identified.is_none() {
+            return report.skip_rest();
+        }
+
+        let status = report.step(DiagnosticStep::Status, || {
+            let status = device.read_status_byte()?;
+            Ok(((), format!("0x{:02X}", status)))
+        });
+        if status.is_none() {
+            return report.skip_rest();
+        }
+
+        report.step(DiagnosticStep::Measure, || {
+            device.start()?;
+            device.read().map(|response| ((), response))
+        });
+
+        report.skip_rest()
+    }
+
+    /// Check if every step passed
+    pub fn passed(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|(_, outcome)| matches!(outcome, DiagnosticOutcome::Passed(_)))
+    }
+
+    /// Get the first failed step, i.e. the layer to look at
+    pub fn first_failure(&self) -> Option<(DiagnosticStep, &str)> {
+        self.steps.iter().find_map(|(step, outcome)| match outcome {
+            DiagnosticOutcome::Failed(error) => Some((*step, error.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Internal method: Run a step and record its outcome
+    fn step<T>(
+        &mut self,
+        step: DiagnosticStep,
+        action: impl FnOnce() -> Result<(T, String)>,
+    ) -> Option<T> {
+        match action() {
+            Ok((value, details)) => {
+                self.steps.push((step, DiagnosticOutcome::Passed(details)));
+                Some(value)
+            }
+            Err(e) => {
+                self.steps
+                    .push((step, DiagnosticOutcome::Failed(format!("{:#}", e))));
+                None
+            }
+        }
+    }
+
+    /// Internal method: Mark all steps not run yet as skipped
+    fn skip_rest(mut self) -> Self {
+        let all = [
+            DiagnosticStep::Context,
+            DiagnosticStep::Enumerate,
+            DiagnosticStep::Open,
+            DiagnosticStep::Identify,
+            DiagnosticStep::Status,
+            DiagnosticStep::Measure,
+        ];
+
+        for step in all.into_iter().skip(self.steps.len()) {
+            self.steps.push((step, DiagnosticOutcome::Skipped));
+        }
+
+        self
+    }
+}
+
+impl fmt::Display for DiagnosticReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (step, outcome) in &self.steps {
+            match outcome {
+                DiagnosticOutcome::Passed(details) => {
+                    writeln!(f, "[PASS] {}: {}", step.name(), details)?
+                }
+                DiagnosticOutcome::Failed(error) => {
+                    writeln!(f, "[FAIL] {}: {}", step.name(), error)?
+                }
+                DiagnosticOutcome::Skipped => writeln!(f, "[SKIP] {}", step.name())?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+//! Instrument-agnostic digital multimeter interface
+
+use anyhow::{Result, anyhow};
+use num_traits::FromPrimitive;
+
+use crate::{Device, DeviceInfo, FunctionCode, RawRange, Reading, device::scpi::range_code};
+
+/// Measurement function of a digital multimeter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DmmFunction {
+    /// DC voltage
+    DcVoltage,
+
+    /// AC voltage
+    AcVoltage,
+
+    /// DC current
+    DcCurrent,
+
+    /// AC current
+    AcCurrent,
+
+    /// 2-wire resistance
+    Resistance,
+
+    /// Frequency
+    Frequency,
+
+    /// Diode test
+    Diode,
+
+    /// Continuity test
+    Continuity,
+}
+
+/// High-level digital multimeter interface
+///
+/// Lets downstream code be written against any meter, not only the ADCMT 7351A.
+pub trait Dmm {
+    /// Identify the instrument, e.g. with the `*IDN?` response
+    fn identify(&mut self) -> Result<DeviceInfo>;
+
+    /// Configure the measurement function and range
+    ///
+    /// `range` is the largest expected magnitude in the base unit of the function (the
+    /// smallest range covering it is selected), `None` selects auto range. The range is
+    /// ignored for frequency.
+    fn configure(&mut self, function: DmmFunction, range: Option<f64>) -> Result<()>;
+
+    /// Trigger a measurement and read back its reading
+    fn read_value(&mut self) -> Result<Reading>;
+}
+
+impl Dmm for Device {
+    fn identify(&mut self) -> Result<DeviceInfo> {
+        Device::identify(self)
+    }
+
+    fn configure(&mut self, function: DmmFunction, range: Option<f64>) -> Result<()> {
+        let function_code = match function {
+            DmmFunction::DcVoltage => FunctionCode::DCV,
+            DmmFunction::AcVoltage => FunctionCode::ACV,
+            DmmFunction::DcCurrent => FunctionCode::DCI,
+            DmmFunction::AcCurrent => FunctionCode::ACI,
+            DmmFunction::Resistance => FunctionCode::Resistance,
+            DmmFunction::Frequency => FunctionCode::Frequency,
+            DmmFunction::Diode => FunctionCode::Diode,
+            DmmFunction::Continuity => FunctionCode::Continuity,
+        };
+
+        let range_code = match (&function_code, range) {
+            // No range selection for these functions
+            (FunctionCode::Diode | FunctionCode::Continuity, None) => None,
+            // The expected frequency needs no range selection, keep the current range
+            (FunctionCode::Frequency, _) => None,
+            (_, None) => Some(range_code(function_code.clone(), "AUTO")?),
+            (_, Some(range)) => Some(range_code(function_code.clone(), &range.to_string())?),
+        };
+
+        self.function_set(function_code)?;
+        if let Some(code) = range_code {
+            let raw_range =
+                RawRange::from_u8(code).ok_or_else(|| anyhow!("Unknown range code {}", code))?;
+            self.range_set(raw_range)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_value(&mut self) -> Result<Reading> {
+        self.start()?;
+        self.fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dmm, DmmFunction};
+    use crate::{Device, RawRange, SimulatedDevice, Unit};
+
+    #[test]
+    fn configure_selects_smallest_covering_range() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+
+        device
+            .configure(DmmFunction::DcVoltage, Some(15.0))
+            .unwrap();
+        assert_eq!(device.range().unwrap(), RawRange::R5);
+
+        let reading = device.read_value().unwrap();
+        assert_eq!(reading.unit, Unit::Volt);
+        assert!(!reading.overload);
+
+        assert!(device.configure(DmmFunction::Diode, Some(1.0)).is_err());
+
+        device.configure(DmmFunction::Frequency, Some(1e3)).unwrap();
+        assert_eq!(device.range().unwrap(), RawRange::R5);
+    }
+}
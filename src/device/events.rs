@@ -0,0 +1,45 @@
+//! Device event subscription
+
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use crate::{FunctionCode, RawRange};
+
+/// State change events of a device
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    /// The measurement function was changed through this handle
+    FunctionChanged(FunctionCode),
+
+    /// The measurement range was changed through this handle
+    RangeChanged(RawRange),
+
+    /// A fetched reading is overloaded, after a reading in range
+    OverloadStarted,
+
+    /// A fetched reading is back in range, after an overloaded reading
+    OverloadCleared,
+
+    /// The device stopped responding because it was disconnected
+    Disconnected,
+}
+
+/// Fan-out of events to all subscribers
+#[derive(Debug, Default)]
+pub(crate) struct EventBus {
+    subscribers: Vec<Sender<DeviceEvent>>,
+}
+
+impl EventBus {
+    /// Add a subscriber and return its receiving end
+    pub(crate) fn subscribe(&mut self) -> Receiver<DeviceEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Send an event to all subscribers, dropping the ones that hung up
+    pub(crate) fn emit(&mut self, event: DeviceEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+    }
+}
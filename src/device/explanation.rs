@@ -0,0 +1,98 @@
+//! Human-readable explanations for common failures
+
+use std::fmt;
+
+/// Explanation of a failure with a suggested remedy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// What most likely went wrong
+    pub message: &'static str,
+
+    /// What the user can do about it
+    pub remedy: &'static str,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.remedy)
+    }
+}
+
+/// Extension trait attaching explanations to errors returned by this crate
+pub trait Explain {
+    /// Get an explanation of the error, if the failure mode is known
+    fn explanation(&self) -> Option<Explanation>;
+}
+
+impl Explain for anyhow::Error {
+    fn explanation(&self) -> Option<Explanation> {
+        // Outermost known cause wins, it carries the most specific meaning
+        self.chain()
+            .find_map(|cause| cause.downcast_ref::<rusb::Error>())
+            .map(explain_usb_error)
+    }
+}
+
+/// Explanation for a USB failure mode
+pub fn explain_usb_error(error: &rusb::Error) -> Explanation {
+    let (message, remedy) = match error {
+        rusb::Error::Access => (
+            "Permission to access the USB device was denied",
+            "on Linux install a udev rule granting access to VID 1334 / PID 0203, on Windows bind the WinUSB driver with Zadig",
+        ),
+        rusb::Error::Busy => (
+            "The USB interface is claimed by another program or kernel driver",
+            "close other programs using the meter, or detach the kernel driver via OpenOptions",
+        ),
+        rusb::Error::NoDevice => (
+            "The device was disconnected or powered off",
+            "check the cable and the power of the meter, then enumerate and open it again",
+        ),
+        rusb::Error::NotFound => (
+            "The device or a requested USB entity was not found",
+            "check that the meter is connected and that the interface is available",
+        ),
+        rusb::Error::Timeout => (
+            "The meter did not answer in time",
+            "increase the timeout for slow sampling rates, or check that the command is valid",
+        ),
+        rusb::Error::Pipe => (
+            "The endpoint stalled, usually after an invalid or interrupted transfer",
+            "clear the device buffers with Device::clear and retry",
+        ),
+        rusb::Error::Overflow => (
+            "The device sent more data than the read buffer could hold",
+            "read with a larger buffer",
+        ),
+        rusb::Error::Io => (
+            "A low-level USB I/O error occurred",
+            "check the cable and avoid unpowered hubs",
+        ),
+        rusb::Error::NotSupported => (
+            "The operation is not supported on this platform or by the installed driver",
+            "on Windows make sure the WinUSB driver is bound to the device",
+        ),
+        rusb::Error::Interrupted => (
+            "The USB operation was interrupted by a signal",
+            "retry the operation",
+        ),
+        rusb::Error::NoMem => (
+            "The system ran out of memory for the USB transfer",
+            "free system memory and retry",
+        ),
+        rusb::Error::InvalidParam => (
+            "An invalid parameter was passed to the USB stack",
+            "this is likely a bug, please report it",
+        ),
+        rusb::Error::BadDescriptor => (
+            "The device returned a malformed USB descriptor",
+            "reconnect the meter; if it persists, its firmware may be faulty",
+        ),
+        rusb::Error::Other => (
+            "An unknown USB error occurred",
+            "reconnect the meter and retry",
+        ),
+    };
+
+    Explanation { message, remedy }
+}
@@ -2,25 +2,130 @@
 
 use anyhow::{Context, Ok, Result};
 
-use crate::transport::{UsbContext, UsbDeviceMetadata};
+use crate::{
+    Device, DeviceAliases,
+    transport::{SimulatedDevice, UsbContext, UsbDeviceMetadata},
+};
+
+/// Transport backend used by the device manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Backend {
+    /// libusb backend through rusb
+    #[default]
+    Rusb,
+
+    /// Pure-Rust backend through nusb, not available yet
+    ///
+    /// Building a device manager with it fails until a nusb transport is implemented.
+    Nusb,
+
+    /// Simulated instrument, no USB access (for tests and hardware-free development)
+    ///
+    /// Provides a single simulated device with serial number
+    /// [`SimulatedDevice::SERIAL_NUMBER`]. It cannot be listed as USB metadata, open it with
+    /// [`DeviceManager::open_first`] or by an alias assigned to that serial number.
+    Mock,
+}
+
+/// Builder for a device manager
+#[derive(Debug, Default)]
+pub struct DeviceManagerBuilder {
+    backend: Backend,
+}
+
+impl DeviceManagerBuilder {
+    /// Select the transport backend
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Build the device manager, initializing the selected backend
+    pub fn build(self) -> Result<DeviceManager> {
+        let ctx = match self.backend {
+            Backend::Rusb => Some(UsbContext::new().context("Failed to initialize USB context")?),
+            Backend::Nusb => {
+                return Err(anyhow::anyhow!("The nusb backend is not available yet"));
+            }
+            Backend::Mock => None,
+        };
+
+        Ok(DeviceManager {
+            backend: self.backend,
+            ctx,
+        })
+    }
+}
+
+/// Device metadata enriched with the identity reported by `*IDN?`
+#[derive(Debug, Clone)]
+pub struct IdentifiedDevice {
+    /// USB metadata of the device
+    pub metadata: UsbDeviceMetadata,
+
+    /// Model name, `None` if the device could not be queried
+    pub model: Option<String>,
+
+    /// Firmware revision, `None` if the device could not be queried
+    pub firmware: Option<String>,
+}
 
 /// Device manager for enumerating available devices
 pub struct DeviceManager {
-    ctx: UsbContext,
+    backend: Backend,
+    ctx: Option<UsbContext>,
 }
 
 impl DeviceManager {
-    /// Create a new device manager
+    /// Create a new device manager with the default backend
     pub fn new() -> Result<Self> {
-        let ctx = UsbContext::new().context("Failed to initialize USB context")?;
-        Ok(Self { ctx })
+        Self::builder().build()
+    }
+
+    /// Create a builder to configure the device manager
+    pub fn builder() -> DeviceManagerBuilder {
+        DeviceManagerBuilder::default()
+    }
+
+    /// Get the transport backend in use
+    pub fn backend(&self) -> Backend {
+        self.backend
     }
 
     /// List all available ADCMT 7351 devices
+    ///
+    /// The mock backend has no USB devices to list, open it with [`DeviceManager::open_first`].
     pub fn list_devices(&self) -> Result<Vec<UsbDeviceMetadata>> {
-        self.ctx
-            .enumerate_devices()
-            .context("Failed to enumerate USB devices")
+        match &self.ctx {
+            Some(ctx) => ctx
+                .enumerate_devices()
+                .context("Failed to enumerate USB devices"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// List all available devices together with their model and firmware
+    ///
+    /// Each device is opened briefly to query `*IDN?`. Devices that cannot be opened (e.g.
+    /// already in use) are still listed, without model and firmware.
+    pub fn list_identified(&self) -> Result<Vec<IdentifiedDevice>> {
+        let devices = self.list_devices()?;
+
+        Ok(devices
+            .into_iter()
+            .map(|metadata| {
+                let info = Device::open(&metadata)
+                    .and_then(|mut device| device.identify())
+                    .ok();
+
+                IdentifiedDevice {
+                    model: info.as_ref().map(|info| info.model.clone()),
+                    firmware: info.map(|info| info.firmware),
+                    metadata,
+                }
+            })
+            .collect())
     }
 
     /// Get the first available device info
@@ -31,10 +136,105 @@ impl DeviceManager {
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("No ADCMT 7351 device found"))
     }
+
+    /// Open the first available device
+    ///
+    /// With the mock backend, this opens a fresh simulated instrument.
+    pub fn open_first(&self) -> Result<Device> {
+        match self.backend {
+            Backend::Mock => Ok(Device::from_transport(Box::new(SimulatedDevice::new()))),
+            _ => Device::open(&self.first_device()?),
+        }
+    }
+
+    /// Find the device with the given serial number
+    pub fn find_by_serial(&self, serial: &str) -> Result<UsbDeviceMetadata> {
+        self.list_devices()?
+            .into_iter()
+            .find(|metadata| metadata.serial_number.as_deref() == Some(serial))
+            .ok_or_else(|| anyhow::anyhow!("No ADCMT 7351 device with serial '{}' found", serial))
+    }
+
+    /// Open the device assigned to an alias in the default alias file
+    pub fn open_by_alias(&self, alias: &str) -> Result<Device> {
+        let aliases = DeviceAliases::load_default().context("Failed to load device aliases")?;
+        self.open_by_alias_in(&aliases, alias)
+    }
+
+    /// Open the device assigned to an alias in the given alias mapping
+    pub fn open_by_alias_in(&self, aliases: &DeviceAliases, alias: &str) -> Result<Device> {
+        let serial = aliases
+            .serial(alias)
+            .ok_or_else(|| anyhow::anyhow!("Unknown device alias '{}'", alias))?;
+
+        if self.backend == Backend::Mock {
+            if serial != SimulatedDevice::SERIAL_NUMBER {
+                return Err(anyhow::anyhow!(
+                    "No ADCMT 7351 device with serial '{}' found",
+                    serial
+                ));
+            }
+            return Ok(Device::from_transport(Box::new(SimulatedDevice::new())));
+        }
+
+        let metadata = self.find_by_serial(serial)?;
+        Device::open(&metadata).with_context(|| format!("Failed to open device '{}'", alias))
+    }
 }
 
+/// Deprecated: panics when the USB context cannot be created.
+///
+/// Use [`DeviceManager::new`] or [`DeviceManager::builder`] to handle the failure instead.
 impl Default for DeviceManager {
     fn default() -> Self {
         Self::new().expect("Failed to create device manager")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_backend_opens_simulated_device() {
+        let manager = DeviceManager::builder()
+            .backend(Backend::Mock)
+            .build()
+            .unwrap();
+
+        assert!(manager.list_devices().unwrap().is_empty());
+
+        let mut device = manager.open_first().unwrap();
+        assert_eq!(
+            device.identify().unwrap().serial_number,
+            SimulatedDevice::SERIAL_NUMBER
+        );
+    }
+
+    #[test]
+    fn mock_backend_resolves_aliases_by_serial() {
+        let manager = DeviceManager::builder()
+            .backend(Backend::Mock)
+            .build()
+            .unwrap();
+        let mut aliases = DeviceAliases::new();
+        aliases
+            .set("bench", SimulatedDevice::SERIAL_NUMBER)
+            .unwrap();
+        aliases.set("other", "12345").unwrap();
+
+        manager.open_by_alias_in(&aliases, "bench").unwrap();
+        assert!(manager.open_by_alias_in(&aliases, "other").is_err());
+        assert!(manager.open_by_alias_in(&aliases, "unknown").is_err());
+    }
+
+    #[test]
+    fn nusb_backend_is_not_available_yet() {
+        assert!(
+            DeviceManager::builder()
+                .backend(Backend::Nusb)
+                .build()
+                .is_err()
+        );
+    }
+}
@@ -1,8 +1,40 @@
 //! Device layer for instrument communication
 
+mod aliases;
+#[cfg(feature = "tokio")]
+mod async_device;
+mod audit;
+mod commands;
+mod diagnostics;
+mod dmm;
+mod events;
+mod explanation;
 mod manager;
 mod operations;
+mod parsers;
+mod policy;
+mod read_only;
+mod scpi;
+mod snapshot;
+mod state;
+mod trace;
 
 // Re-exports
-pub use manager::DeviceManager;
+pub use aliases::DeviceAliases;
+#[cfg(feature = "tokio")]
+pub use async_device::AsyncDevice;
+pub use audit::{AuditEntry, AuditLog};
+pub use commands::{CommandDescription, ParameterKind, command_reference, command_reference_json};
+pub use diagnostics::{DiagnosticOutcome, DiagnosticReport, DiagnosticStep};
+pub use dmm::{Dmm, DmmFunction};
+pub use events::DeviceEvent;
+pub use explanation::{Explain, Explanation, explain_usb_error};
+pub use manager::{Backend, DeviceManager, DeviceManagerBuilder, IdentifiedDevice};
 pub use operations::*;
+pub use parsers::{ResponseParsers, enumerated, strip_header};
+pub use policy::{CommandFamily, CommandPolicy, PolicyError};
+pub use read_only::ReadOnlyError;
+pub use scpi::{ScpiReply, ScpiTranslation, translate_scpi};
+pub use snapshot::DisplaySnapshot;
+pub use state::{ConnectionStatus, DeviceState, StateRefresher};
+pub use trace::{annotate_command, export_trace};
@@ -1,17 +1,59 @@
 //! Instrument operations
 
-use std::time::Duration;
+#[cfg(unix)]
+use std::os::fd::RawFd;
+use std::{
+    sync::mpsc::Receiver,
+    time::{Duration, Instant, SystemTime},
+};
 
-use anyhow::{Context, Ok, Result};
+use anyhow::{Context, Ok, Result, anyhow};
 
 use crate::{
+    AuditEntry, AuditLog, CommandPolicy, DeviceEvent, FunctionCode, RawRange, ReadOnlyError,
+    ResponseParsers,
+    device::{
+        events::EventBus,
+        read_only::{is_query, is_read_only_safe},
+    },
     protocol::{Packet, SequenceCounter},
-    transport::{UsbDevice, UsbDeviceMetadata},
+    transport::{OpenOptions, Transport, UsbDevice, UsbDeviceMetadata},
 };
 
+/// Default size of the buffer a single response transfer is read into
+const DEFAULT_READ_BUFFER_SIZE: usize = 128;
+
+/// Smallest read buffer, holding the packet header and a few bytes of data
+const MIN_READ_BUFFER_SIZE: usize = 16;
+
+/// Shortest interval between two status byte polls
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Time the instrument needs to settle after a reset before it accepts commands
+const RESET_SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Default maximum length of a reassembled response
+const DEFAULT_MAX_RESPONSE_LEN: usize = 64 * 1024;
+
 pub struct Device {
-    usb_device: UsbDevice,
+    transport: Box<dyn Transport>,
     sequence: SequenceCounter,
+    events: EventBus,
+    disconnected: bool,
+    overloaded: bool,
+    known_function: Option<FunctionCode>,
+    known_range: Option<RawRange>,
+    read_only: bool,
+    usbtmc: bool,
+    read_buffer_size: usize,
+    max_response_len: usize,
+    response_delay: Duration,
+    status_polling: bool,
+    write_delay: Duration,
+    policy: CommandPolicy,
+    audit: Option<AuditLog>,
+    audit_tag: Option<String>,
+    pub(crate) parsers: ResponseParsers,
 }
 
 impl Device {
@@ -19,69 +61,605 @@ impl Device {
     pub fn open(metadata: &UsbDeviceMetadata) -> Result<Self> {
         let usb_device = UsbDevice::open(metadata).context("Failed to open USB device")?;
 
-        Ok(Self {
-            usb_device,
+        Ok(Self::from_transport(Box::new(usb_device)))
+    }
+
+    /// Open a multimeter device that only permits queries and reads
+    ///
+    /// State-changing commands are rejected with a [`ReadOnlyError`], so monitoring tools can
+    /// attach to a production meter without risk of changing its configuration.
+    pub fn open_read_only(metadata: &UsbDeviceMetadata) -> Result<Self> {
+        let usb_device = UsbDevice::open(metadata).context("Failed to open USB device")?;
+
+        Ok(Self::from_transport_read_only(Box::new(usb_device)))
+    }
+
+    /// Open a multimeter device using device metadata and custom open options
+    pub fn open_with_options(metadata: &UsbDeviceMetadata, options: &OpenOptions) -> Result<Self> {
+        let usb_device =
+            UsbDevice::open_with_options(metadata, options).context("Failed to open USB device")?;
+
+        let mut device = Self::from_transport(Box::new(usb_device));
+        device.set_status_polling(options.status_polling);
+        Ok(device)
+    }
+
+    /// Open a multimeter device from an already opened USB file descriptor
+    ///
+    /// Intended for Android, where the USB host API of the platform grants access to the
+    /// device and hands out its file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be a valid usbfs file descriptor, and it must stay open for as long as the
+    /// returned device is alive.
+    #[cfg(unix)]
+    pub unsafe fn from_raw_fd(fd: RawFd, options: &OpenOptions) -> Result<Self> {
+        let usb_device = unsafe { UsbDevice::from_raw_fd(fd, options) }
+            .context("Failed to open USB device from file descriptor")?;
+
+        let mut device = Self::from_transport(Box::new(usb_device));
+        device.set_status_polling(options.status_polling);
+        Ok(device)
+    }
+
+    /// Open a multimeter device over an alternative transport
+    pub fn from_transport(transport: Box<dyn Transport>) -> Self {
+        Self::from_transport_with_mode(transport, false)
+    }
+
+    /// Open a multimeter device over an alternative transport that only permits queries
+    pub fn from_transport_read_only(transport: Box<dyn Transport>) -> Self {
+        Self::from_transport_with_mode(transport, true)
+    }
+
+    /// Internal method: Wrap a transport, optionally as a read-only handle
+    fn from_transport_with_mode(transport: Box<dyn Transport>, read_only: bool) -> Self {
+        let write_delay = transport.write_delay();
+        let mut device = Self {
+            transport,
             sequence: SequenceCounter::new(),
-        })
+            events: EventBus::default(),
+            disconnected: false,
+            overloaded: false,
+            known_function: None,
+            known_range: None,
+            read_only,
+            usbtmc: false,
+            read_buffer_size: DEFAULT_READ_BUFFER_SIZE,
+            max_response_len: DEFAULT_MAX_RESPONSE_LEN,
+            response_delay: Duration::from_millis(10),
+            status_polling: false,
+            write_delay,
+            policy: CommandPolicy::default(),
+            audit: None,
+            audit_tag: None,
+            parsers: ResponseParsers::new(),
+        };
+
+        // Align the default buffer to the packets of the transport
+        device.set_read_buffer_size(DEFAULT_READ_BUFFER_SIZE);
+
+        device
+    }
+
+    /// Check if this handle rejects state-changing commands
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Protocol: use spec-compliant USBTMC framing instead of the default framing
+    ///
+    /// Write packets carry the exact TransferSize, read requests ask for the full buffer, and
+    /// responses are checked for a matching bTag and reassembled until the EOM bit is set.
+    pub fn set_usbtmc_mode(&mut self, enabled: bool) {
+        self.usbtmc = enabled;
+    }
+
+    /// Protocol: check if spec-compliant USBTMC framing is used
+    pub fn is_usbtmc_mode(&self) -> bool {
+        self.usbtmc
+    }
+
+    /// Policy: restrict which command families this handle may send
+    pub fn set_command_policy(&mut self, policy: CommandPolicy) {
+        self.policy = policy;
+    }
+
+    /// Policy: get the command policy of this handle
+    pub fn command_policy(&self) -> &CommandPolicy {
+        &self.policy
+    }
+
+    /// Audit: record every state-changing command into the given audit log
+    pub fn set_audit_log(&mut self, audit: AuditLog) {
+        self.audit = Some(audit);
+    }
+
+    /// Audit: get the audit log, if enabled
+    pub fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit.as_ref()
+    }
+
+    /// Audit: stop auditing and return the audit log
+    pub fn take_audit_log(&mut self) -> Option<AuditLog> {
+        self.audit.take()
+    }
+
+    /// Audit: set the tag recorded with subsequent commands (e.g. test step or operator)
+    pub fn set_audit_tag(&mut self, tag: Option<&str>) {
+        self.audit_tag = tag.map(str::to_string);
+    }
+
+    /// Events: subscribe to state change events of this device
+    ///
+    /// Every call returns a new receiver; all receivers get every event emitted afterwards.
+    pub fn events(&mut self) -> Receiver<DeviceEvent> {
+        self.events.subscribe()
+    }
+
+    /// Internal method: Emit an event to all subscribers
+    pub(crate) fn emit(&mut self, event: DeviceEvent) {
+        self.events.emit(event);
+    }
+
+    /// Internal method: Track the function set through this handle and emit its changes
+    pub(crate) fn update_function(&mut self, function: FunctionCode) {
+        if self.known_function.as_ref() != Some(&function) {
+            self.known_function = Some(function.clone());
+            self.emit(DeviceEvent::FunctionChanged(function));
+        }
+    }
+
+    /// Internal method: Track the range set through this handle and emit its changes
+    pub(crate) fn update_range(&mut self, range: RawRange) {
+        if self.known_range.as_ref() != Some(&range) {
+            self.known_range = Some(range.clone());
+            self.emit(DeviceEvent::RangeChanged(range));
+        }
+    }
+
+    /// Internal method: Track the overload state of readings and emit its transitions
+    pub(crate) fn update_overload(&mut self, overload: bool) {
+        if overload != self.overloaded {
+            self.overloaded = overload;
+            self.emit(if overload {
+                DeviceEvent::OverloadStarted
+            } else {
+                DeviceEvent::OverloadCleared
+            });
+        }
+    }
+
+    /// Internal method: Emit a disconnect event once if the error means the device is gone
+    fn check_disconnect<T>(&mut self, result: Result<T>) -> Result<T> {
+        if let Err(e) = &result {
+            let gone = e
+                .chain()
+                .any(|cause| cause.downcast_ref::<rusb::Error>() == Some(&rusb::Error::NoDevice));
+
+            if gone && !self.disconnected {
+                self.disconnected = true;
+                self.emit(DeviceEvent::Disconnected);
+            }
+        }
+
+        result
     }
 
     /// Set timeout for operation IO
     pub fn set_timeout(&mut self, timeout: Duration) {
-        self.usb_device.set_timeout(timeout);
+        self.transport.set_timeout(timeout);
     }
 
     /// Get timeout of operation IO
     pub fn timeout(&self) -> Duration {
-        self.usb_device.timeout()
+        self.transport.timeout()
+    }
+
+    /// Set delay between a read request and reading the response
+    ///
+    /// With status polling enabled, this is the longest interval between two polls instead.
+    pub fn set_response_delay(&mut self, delay: Duration) {
+        self.response_delay = delay;
+    }
+
+    /// Get delay between a read request and reading the response
+    pub fn response_delay(&self) -> Duration {
+        self.response_delay
+    }
+
+    /// Wait for responses by polling the MAV bit of the status byte instead of fixed delays
+    ///
+    /// Polls start 1 ms apart and back off up to the response delay. While enabled, the write
+    /// delay of the transport (see [`OpenOptions::write_delay`]) is skipped as well. Disabled
+    /// by default, see [`OpenOptions::status_polling`] to enable it when opening.
+    pub fn set_status_polling(&mut self, enabled: bool) {
+        self.status_polling = enabled;
+        self.transport.set_write_delay(if enabled {
+            Duration::ZERO
+        } else {
+            self.write_delay
+        });
+    }
+
+    /// Check if responses are awaited by polling the status byte
+    pub fn is_status_polling(&self) -> bool {
+        self.status_polling
+    }
+
+    /// Set size of the buffer a single response transfer is read into
+    ///
+    /// The size is rounded up to a multiple of the max packet size of the transport (and to
+    /// at least 16 bytes), so a transfer never ends in the middle of a packet. Larger buffers
+    /// need fewer transfers for long responses such as memory dumps.
+    pub fn set_read_buffer_size(&mut self, size: usize) {
+        let max_packet_size = self.transport.max_packet_size().max(1);
+        self.read_buffer_size =
+            size.max(MIN_READ_BUFFER_SIZE).div_ceil(max_packet_size) * max_packet_size;
+    }
+
+    /// Get size of the buffer a single response transfer is read into
+    pub fn read_buffer_size(&self) -> usize {
+        self.read_buffer_size
+    }
+
+    /// Set maximum length of a response reassembled from multiple transfers
+    pub fn set_max_response_len(&mut self, len: usize) {
+        self.max_response_len = len;
+    }
+
+    /// Get maximum length of a response reassembled from multiple transfers
+    pub fn max_response_len(&self) -> usize {
+        self.max_response_len
     }
 
     /// Write a command to current device
     pub fn write(&mut self, command: &str) -> Result<()> {
+        let timestamp = SystemTime::now();
+        let result = self.write_unaudited(command);
+
+        if !is_query(command)
+            && let Some(audit) = &mut self.audit
+        {
+            audit
+                .record(AuditEntry {
+                    timestamp,
+                    tag: self.audit_tag.clone(),
+                    command: command.to_string(),
+                    result: result.as_ref().map(|_| ()).map_err(|e| format!("{:#}", e)),
+                })
+                .context("Failed to record command in audit log")?;
+        }
+
+        result
+    }
+
+    /// Internal method: Write a command without recording it in the audit log
+    fn write_unaudited(&mut self, command: &str) -> Result<()> {
+        if self.read_only && !is_read_only_safe(command) {
+            return Err(ReadOnlyError {
+                command: command.to_string(),
+            }
+            .into());
+        }
+
+        self.policy.check(command)?;
+
         let sequence = self.sequence.next();
-        let packet =
-            Packet::encode_write(command, sequence).context("Failed to encode write packet")?;
+        let packet = if self.usbtmc {
+            Packet::encode_write_usbtmc(command, sequence)
+        } else {
+            Packet::encode_write(command, sequence)
+        }
+        .context("Failed to encode write packet")?;
 
-        self.usb_device
+        let result = self
+            .transport
             .write(&packet)
-            .context("Failed to write command to current device")?;
+            .context("Failed to write command to current device");
+        self.check_disconnect(result)?;
 
         Ok(())
     }
 
     /// Read a response from the device
     pub fn read(&mut self) -> Result<String> {
+        if self.usbtmc {
+            return self.read_usbtmc();
+        }
+
         // Send read request
         let sequence = self.sequence.next();
         let read_request = Packet::encode_read(sequence);
 
-        self.usb_device
+        let result = self
+            .transport
             .write(&read_request)
-            .context("Failed to send read request")?;
+            .context("Failed to send read request");
+        self.check_disconnect(result)?;
 
         // Wait for device to interact
-        std::thread::sleep(Duration::from_millis(10));
+        self.wait_for_response()?;
 
         // Read response
-        let mut buffer = vec![0u8; 128];
-        let transferred = self
-            .usb_device
+        let mut buffer = vec![0u8; self.read_buffer_size];
+        let result = self
+            .transport
             .read(&mut buffer)
-            .context("Failed to read from device")?;
+            .context("Failed to read from device");
+        let transferred = self.check_disconnect(result)?;
+        buffer.truncate(transferred);
+
+        // Keep reading transfers until the TransferSize of the header is reached
+        if let Some(response_len) = Packet::read_response_len(&buffer) {
+            if response_len - 12 > self.max_response_len {
+                return Err(anyhow!(
+                    "Response of {} bytes exceeds the maximum response length of {} bytes",
+                    response_len - 12,
+                    self.max_response_len
+                ));
+            }
+
+            // A transfer ending on a short packet is the last one of the response, the data
+            // received so far is decoded even if it falls short of the TransferSize
+            let max_packet_size = self.transport.max_packet_size().max(1);
+            let mut ended = transferred % max_packet_size != 0;
+
+            while buffer.len() < response_len && !ended {
+                let mut chunk = vec![0u8; self.read_buffer_size];
+                let result = self
+                    .transport
+                    .read(&mut chunk)
+                    .context("Failed to read continuation from device");
+                let transferred = self.check_disconnect(result)?;
+
+                buffer.extend_from_slice(&chunk[..transferred]);
+                ended = transferred == 0 || transferred % max_packet_size != 0;
+            }
+        }
 
         // Decode packet
-        let decoded = Packet::decode_read(&buffer[..transferred])
-            .context("Failed to decode read response")?;
+        let decoded =
+            Packet::decode_read(&buffer, sequence).context("Failed to decode read response")?;
 
         // Convert to String
         String::from_utf8(decoded).context("Response contains invalid UTF-8 character")
     }
 
+    /// Internal method: Wait until the device has a response ready
+    fn wait_for_response(&mut self) -> Result<()> {
+        if !self.status_polling {
+            std::thread::sleep(self.response_delay);
+            return Ok(());
+        }
+
+        let deadline = Instant::now() + self.timeout();
+        let max_interval = self.response_delay.max(MIN_POLL_INTERVAL);
+        let mut interval = MIN_POLL_INTERVAL;
+
+        loop {
+            let result = self.status();
+            let status = self.check_disconnect(result)?;
+            if status.message_available() {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(anyhow::Error::new(rusb::Error::Timeout))
+                    .context("Timed out waiting for a response to become available");
+            }
+
+            std::thread::sleep(interval);
+            interval = (interval * 2).min(max_interval);
+        }
+    }
+
+    /// Internal method: Read a response with USBTMC framing, reassembling it until EOM
+    fn read_usbtmc(&mut self) -> Result<String> {
+        let mut message = Vec::new();
+
+        loop {
+            // Send read request
+            let sequence = self.sequence.next();
+            let read_request =
+                Packet::encode_read_usbtmc(sequence, (self.read_buffer_size - 12) as u32);
+
+            let result = self
+                .transport
+                .write(&read_request)
+                .context("Failed to send read request");
+            self.check_disconnect(result)?;
+
+            // Wait for device to interact
+            self.wait_for_response()?;
+
+            // Read response
+            let mut buffer = vec![0u8; self.read_buffer_size];
+            let result = self
+                .transport
+                .read(&mut buffer)
+                .context("Failed to read from device");
+            let transferred = self.check_disconnect(result)?;
+
+            // Decode packet
+            let (data, eom) = Packet::decode_read_usbtmc(&buffer[..transferred], sequence)
+                .context("Failed to decode read response")?;
+            message.extend_from_slice(&data);
+
+            if message.len() > self.max_response_len {
+                return Err(anyhow!(
+                    "Response exceeds the maximum response length of {} bytes",
+                    self.max_response_len
+                ));
+            }
+
+            if eom {
+                break;
+            }
+        }
+
+        // Strip trailing CR/LF
+        while let Some(b'\r' | b'\n') = message.last() {
+            message.pop();
+        }
+
+        // Convert to String
+        String::from_utf8(message).context("Response contains invalid UTF-8 character")
+    }
+
+    /// Read the raw status byte via control transfer
+    pub(crate) fn read_status_byte(&self) -> Result<u8> {
+        self.transport
+            .read_status()
+            .context("Failed to read status byte")
+    }
+
     /// Clear device input/output buffers
     pub fn clear(&mut self) -> Result<()> {
-        self.usb_device
+        self.transport
             .clear_halt()
             .context("Failed to clear device buffers")?;
 
         Ok(())
     }
+
+    /// Reset the device and synchronize with it afterwards
+    ///
+    /// Sends `*RST`, waits for the instrument to settle, discards pending responses, restarts
+    /// the sequence numbers and then retries a function query until the device answers or
+    /// the IO timeout elapses.
+    ///
+    /// ADC command: `*RST`
+    pub fn reset(&mut self) -> Result<()> {
+        self.write("*RST")?;
+
+        // Wait for the instrument to settle
+        std::thread::sleep(RESET_SETTLE_TIME);
+
+        // Drop anything sent before the reset
+        self.clear()?;
+        self.sequence.reset();
+
+        // The reset restores defaults behind the tracked settings
+        self.known_function = None;
+        self.known_range = None;
+
+        // Re-validate communication with a lightweight query
+        let deadline = Instant::now() + self.timeout();
+        loop {
+            match self.function() {
+                Result::Ok(_) => return Ok(()),
+                Err(e) if Instant::now() >= deadline || self.disconnected => {
+                    return Err(e).context("Device did not respond after reset");
+                }
+                Err(_) => std::thread::sleep(RESET_SETTLE_TIME),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::Result;
+
+    use crate::{Device, SimulatedDevice, Transport};
+
+    /// Simulated instrument announcing more data in the header than it sends
+    struct TruncatingDevice(SimulatedDevice);
+
+    impl Transport for TruncatingDevice {
+        fn write(&self, data: &[u8]) -> Result<usize> {
+            self.0.write(data)
+        }
+
+        fn read(&self, buffer: &mut [u8]) -> Result<usize> {
+            let transferred = self.0.read(buffer)?;
+            if transferred >= 12 && buffer[0] == 0x02 {
+                buffer[4] = buffer[4].wrapping_add(100);
+            }
+            Result::Ok(transferred)
+        }
+
+        fn set_timeout(&mut self, timeout: Duration) {
+            self.0.set_timeout(timeout)
+        }
+
+        fn timeout(&self) -> Duration {
+            self.0.timeout()
+        }
+
+        fn clear_halt(&self) -> Result<()> {
+            self.0.clear_halt()
+        }
+
+        fn read_status(&self) -> Result<u8> {
+            self.0.read_status()
+        }
+
+        fn set_write_delay(&mut self, delay: Duration) {
+            self.0.set_write_delay(delay)
+        }
+
+        fn write_delay(&self) -> Duration {
+            self.0.write_delay()
+        }
+
+        fn max_packet_size(&self) -> usize {
+            self.0.max_packet_size()
+        }
+    }
+
+    #[test]
+    fn query_round_trip() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        assert!(!device.is_status_polling());
+        device.set_status_polling(true);
+
+        device.write("F5").unwrap();
+        device.write("F?").unwrap();
+        assert_eq!(device.read().unwrap(), "F5");
+
+        // Sequence advances over many exchanges, wrapping around without desync
+        for _ in 0..300 {
+            device.write("*IDN?").unwrap();
+            assert!(device.read().unwrap().starts_with("ADCMT,7351A"));
+        }
+    }
+
+    #[test]
+    fn response_split_over_several_reads() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        device.set_read_buffer_size(16);
+        let comment = "1".repeat(60);
+
+        device.write(&format!("KOM{}", comment)).unwrap();
+        device.write("KOM?").unwrap();
+        assert_eq!(device.read().unwrap(), comment);
+    }
+
+    #[test]
+    fn response_exceeding_max_length_is_rejected() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        device.set_max_response_len(8);
+
+        device.write("*IDN?").unwrap();
+        let error = device.read().unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("exceeds the maximum response length")
+        );
+    }
+
+    #[test]
+    fn short_packet_ends_response_without_waiting() {
+        let mut device = Device::from_transport(Box::new(TruncatingDevice(SimulatedDevice::new())));
+
+        // A continuation read would append simulated measurement data
+        device.write("*IDN?").unwrap();
+        assert_eq!(device.read().unwrap(), "ADCMT,7351A,SIMULATED,1.00");
+    }
 }
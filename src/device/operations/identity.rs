@@ -0,0 +1,63 @@
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+
+use crate::Device;
+
+/// Identity of the device reported by `*IDN?`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// Manufacturer name
+    pub manufacturer: String,
+
+    /// Model name
+    pub model: String,
+
+    /// Serial number
+    pub serial_number: String,
+
+    /// Firmware revision
+    pub firmware: String,
+}
+
+impl DeviceInfo {
+    /// Parse an identity response of the form `<manufacturer>,<model>,<serial>,<firmware>`
+    pub fn parse(response: &str) -> Result<Self> {
+        let fields: Vec<&str> = response.trim().split(',').map(str::trim).collect();
+
+        let [manufacturer, model, serial_number, firmware] = fields[..] else {
+            return Err(anyhow!(
+                "Failed to parse identity '{}': expected 4 comma-separated fields",
+                response
+            ));
+        };
+
+        Ok(Self {
+            manufacturer: manufacturer.to_string(),
+            model: model.to_string(),
+            serial_number: serial_number.to_string(),
+            firmware: firmware.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} (serial {}, firmware {})",
+            self.manufacturer, self.model, self.serial_number, self.firmware
+        )
+    }
+}
+
+impl Device {
+    /// Identity: query manufacturer, model, serial number and firmware revision
+    ///
+    /// ADC command: `*IDN?`
+    pub fn identify(&mut self) -> Result<DeviceInfo> {
+        self.write("*IDN?")?;
+        let response = self.read()?;
+        DeviceInfo::parse(&response)
+    }
+}
@@ -1,3 +1,4 @@
+use adcmt_7351_controller_macros::adc_command;
 use anyhow::{Result, anyhow};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
@@ -226,6 +227,7 @@ pub enum ShortHand {
 }
 
 /// Sampling rate mapping enum
+#[adc_command(query = "PR?", set = "PR{}", response = u8)]
 #[derive(Debug, FromPrimitive, Clone, PartialEq)]
 pub enum SamplingRate {
     /// Fast sampling rate
@@ -242,6 +244,7 @@ pub enum SamplingRate {
 }
 
 /// Number of display digits mapping enum
+#[adc_command(query = "RE?", set = "RE{}", response = u8)]
 #[derive(Debug, FromPrimitive, Clone, PartialEq)]
 pub enum NumberOfDisplayDigits {
     /// 3 1/2 digits
@@ -255,6 +258,7 @@ pub enum NumberOfDisplayDigits {
 }
 
 /// Auto zero mapping enum
+#[adc_command(query = "AZ?", set = "AZ{}", response = u8)]
 #[derive(Debug, FromPrimitive, Clone, PartialEq)]
 pub enum AutoZero {
     /// Manual auto zero
@@ -272,16 +276,7 @@ impl Device {
     ///
     /// ADC command: `F?`
     pub fn function(&mut self) -> Result<FunctionCode> {
-        self.write("F?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("F").unwrap_or(trimmed);
-        let function_code: u8 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse function code '{}': {}", response, e))?;
-
-        FunctionCode::from_u8(function_code)
-            .ok_or_else(|| anyhow!("Failed to convert function code to FunctionCode"))
+        self.query_parsed("F?")
     }
 
     /// Function: change to the given function code
@@ -301,6 +296,8 @@ impl Device {
             return Err(anyhow!("Failed to set function"));
         }
 
+        self.update_function(function_code);
+
         Ok(())
     }
 
@@ -321,15 +318,7 @@ impl Device {
     ///
     /// ADC command: `R?`
     pub fn range(&mut self) -> Result<RawRange> {
-        self.write("R?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("R").unwrap_or(trimmed);
-        let num: u8 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse range value '{}': {}", response, e))?;
-
-        RawRange::from_u8(num).ok_or_else(|| anyhow!("Failed to convert range value to RawRange"))
+        self.query_parsed("R?")
     }
 
     /// Range: set current range of the measurement
@@ -344,6 +333,8 @@ impl Device {
             return Err(anyhow!("Failed to set range"));
         }
 
+        self.update_range(raw_range);
+
         Ok(())
     }
 
@@ -489,6 +480,8 @@ impl Device {
             ));
         }
 
+        self.update_function(actual_function);
+
         // Verify the range if it was set
         if let Some(expected_range_value) = expected_range_opt {
             let actual_range = self.range()?;
@@ -505,107 +498,8 @@ impl Device {
                     actual_range
                 ));
             }
-        }
-
-        Ok(())
-    }
-
-    /// Sampling Rate: get current sampling rate
-    ///
-    /// ADC command: `PR?`
-    pub fn sampling_rate(&mut self) -> Result<SamplingRate> {
-        self.write("PR?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("PR").unwrap_or(trimmed);
-        let num: u8 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse sampling rate value '{}': {}", response, e))?;
-
-        SamplingRate::from_u8(num)
-            .ok_or_else(|| anyhow!("Failed to convert sampling rate value to SamplingRate"))
-    }
-
-    /// Sampling Rate: set current sampling rate
-    ///
-    /// ADC command: `PR<sampling_rate>`
-    pub fn sampling_rate_set(&mut self, sampling_rate: SamplingRate) -> Result<()> {
-        // Set the sampling rate
-        self.write(&format!("PR{}", sampling_rate.clone() as u8))?;
-
-        // Verify the sampling rate
-        if self.sampling_rate()? != sampling_rate {
-            return Err(anyhow!("Failed to set sampling rate"));
-        }
-
-        Ok(())
-    }
-
-    /// Number of Display Digits: get current number of display digits
-    ///
-    /// ADC command: `RE?`
-    pub fn number_of_display_digits(&mut self) -> Result<NumberOfDisplayDigits> {
-        self.write("RE?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("RE").unwrap_or(trimmed);
-        let num: u8 = numeric_part.parse().map_err(|e| {
-            anyhow!(
-                "Failed to parse number of display digits value '{}': {}",
-                response,
-                e
-            )
-        })?;
-
-        NumberOfDisplayDigits::from_u8(num).ok_or_else(|| {
-            anyhow!("Failed to convert number of display digits value to NumberOfDisplayDigits")
-        })
-    }
-
-    /// Number of Display Digits: set current number of display digits
-    ///
-    /// ADC command: `RE<number_of_display_digits>`
-    pub fn number_of_display_digits_set(
-        &mut self,
-        number_of_display_digits: NumberOfDisplayDigits,
-    ) -> Result<()> {
-        // Set the number of display digits
-        self.write(&format!("RE{}", number_of_display_digits.clone() as u8))?;
 
-        // Verify the number of display digits
-        if self.number_of_display_digits()? != number_of_display_digits {
-            return Err(anyhow!("Failed to set number of display digits"));
-        }
-
-        Ok(())
-    }
-
-    /// Auto Zero: get current auto zero setting
-    ///
-    /// ADC command: `AZ?`
-    pub fn auto_zero(&mut self) -> Result<AutoZero> {
-        self.write("AZ?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("AZ").unwrap_or(trimmed);
-        let num: u8 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse auto zero value '{}': {}", response, e))?;
-
-        AutoZero::from_u8(num)
-            .ok_or_else(|| anyhow!("Failed to convert auto zero value to AutoZero"))
-    }
-
-    /// Auto Zero: set current auto zero setting
-    ///
-    /// ADC command: `AZ<auto_zero>`
-    pub fn auto_zero_set(&mut self, auto_zero: AutoZero) -> Result<()> {
-        // Set the auto zero
-        self.write(&format!("AZ{}", auto_zero.clone() as u8))?;
-
-        // Verify the auto zero
-        if self.auto_zero()? != auto_zero {
-            return Err(anyhow!("Failed to set auto zero"));
+            self.update_range(actual_range);
         }
 
         Ok(())
@@ -615,8 +509,7 @@ impl Device {
     ///
     /// ADC command: `KOM?`
     pub fn continuity_threshold_constant(&mut self) -> Result<String> {
-        self.write("KOM?")?;
-        self.read()
+        self.query_parsed("KOM?")
     }
 
     /// Continuity threshold constant: set current continuity threshold constant
@@ -1,9 +1,18 @@
 //! Instrument level operations
 
 mod base;
+mod identity;
 mod measurement;
+mod reading;
+mod self_test;
+mod service_request;
+mod status;
 mod trigger;
 
 pub use base::Device;
+pub use identity::DeviceInfo;
 pub use measurement::*;
+pub use reading::*;
+pub use self_test::SelfTestResult;
+pub use status::StatusByte;
 pub use trigger::*;
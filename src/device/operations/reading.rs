@@ -0,0 +1,198 @@
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+
+use crate::{Device, FunctionCode, ShortHand, TriggerSource};
+
+/// Magnitude from which a value is reported as overload
+const OVERLOAD_THRESHOLD: f64 = 9.9e37;
+
+/// Measurement unit mapping enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Volt (V)
+    Volt,
+
+    /// Ampere (A)
+    Ampere,
+
+    /// Ohm (Ω)
+    Ohm,
+
+    /// Hertz (Hz)
+    Hertz,
+}
+
+impl Unit {
+    /// Infer the unit from the measurement function
+    pub fn from_function(function: &FunctionCode) -> Self {
+        match function {
+            FunctionCode::DCV
+            | FunctionCode::ACV
+            | FunctionCode::ACVCoupling
+            | FunctionCode::Diode => Self::Volt,
+            FunctionCode::DCI | FunctionCode::ACI | FunctionCode::ACICoupling => Self::Ampere,
+            FunctionCode::Resistance
+            | FunctionCode::ResistanceLowPower
+            | FunctionCode::Continuity => Self::Ohm,
+            FunctionCode::Frequency => Self::Hertz,
+        }
+    }
+
+    /// Unit symbol
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Volt => "V",
+            Self::Ampere => "A",
+            Self::Ohm => "Ω",
+            Self::Hertz => "Hz",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// Parsed measurement reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// Measured value in `unit`
+    pub value: f64,
+
+    /// Unit inferred from the active function
+    pub unit: Unit,
+
+    /// Whether the input exceeded the range
+    pub overload: bool,
+}
+
+impl Reading {
+    /// Parse a measurement response for the given function
+    ///
+    /// An alphabetic data header in front of the value is skipped. The reading is flagged
+    /// as overload when the header contains `OL` or the value magnitude is at least
+    /// `9.9E+37`, the conventional overrange value.
+    pub fn parse(response: &str, function: &FunctionCode) -> Result<Self> {
+        let trimmed = response.trim();
+        let numeric_start = trimmed
+            .find(|c: char| c.is_ascii_digit() || matches!(c, '+' | '-' | '.'))
+            .unwrap_or(trimmed.len());
+        let (header, numeric_part) = trimmed.split_at(numeric_start);
+        let header = header.trim().to_ascii_uppercase();
+
+        let unit = Unit::from_function(function);
+
+        // Overload without a numeric value
+        if numeric_part.is_empty() && header.contains("OL") {
+            return Ok(Self {
+                value: f64::INFINITY,
+                unit,
+                overload: true,
+            });
+        }
+
+        let value: f64 = numeric_part
+            .trim()
+            .parse()
+            .map_err(|e| anyhow!("Failed to parse reading value '{}': {}", response, e))?;
+
+        Ok(Self {
+            value,
+            unit,
+            overload: header.contains("OL") || value.abs() >= OVERLOAD_THRESHOLD,
+        })
+    }
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.overload {
+            write!(f, "OL {}", self.unit)
+        } else {
+            write!(f, "{} {}", self.value, self.unit)
+        }
+    }
+}
+
+impl Device {
+    /// Fetch: read the pending measurement data as a typed reading
+    ///
+    /// The unit is inferred from the active function, queried after the data is read.
+    /// Entering and leaving overload is emitted as a [`DeviceEvent`](crate::DeviceEvent).
+    pub fn fetch(&mut self) -> Result<Reading> {
+        let response = self.read()?;
+        let function = self.function()?;
+        let reading = Reading::parse(&response, &function)?;
+        self.update_overload(reading.overload);
+        Ok(reading)
+    }
+
+    /// Measure: configure the function and range, trigger a single conversion and fetch it
+    ///
+    /// Continuous measurement is disabled and the trigger source is set to immediate
+    /// before the conversion is started.
+    pub fn measure(&mut self, shorthand: ShortHand) -> Result<Reading> {
+        self.shorthand_set(shorthand)?;
+        if self.continuously_measure()? {
+            self.continuously_measure_disable()?;
+        }
+        if self.trigger_source()? != TriggerSource::IMMEDIATE {
+            self.trigger_source_set(TriggerSource::IMMEDIATE)?;
+        }
+        self.start()?;
+        self.fetch()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Device, DeviceEvent, FunctionCode, RawRange, ShortHand, SimulatedDevice, VoltageDCRange,
+    };
+
+    #[test]
+    fn fetch_emits_overload_transitions() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        let events = device.events();
+
+        // Echo an overload value through the headerless comment setting
+        device.write("KOM+9.9E+37").unwrap();
+        device.write("KOM?").unwrap();
+        assert!(device.fetch().unwrap().overload);
+        for _ in 0..2 {
+            device.write("INI").unwrap();
+            assert!(!device.fetch().unwrap().overload);
+        }
+
+        let received: Vec<DeviceEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            [DeviceEvent::OverloadStarted, DeviceEvent::OverloadCleared]
+        );
+    }
+
+    #[test]
+    fn repeated_measure_emits_no_false_changes() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        let events = device.events();
+
+        device.measure(ShortHand::DCV(VoltageDCRange::V20)).unwrap();
+        device.measure(ShortHand::DCV(VoltageDCRange::V20)).unwrap();
+        device
+            .measure(ShortHand::DCV(VoltageDCRange::V200))
+            .unwrap();
+
+        let received: Vec<DeviceEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            [
+                DeviceEvent::FunctionChanged(FunctionCode::DCV),
+                DeviceEvent::RangeChanged(RawRange::R5),
+                DeviceEvent::RangeChanged(RawRange::R6),
+            ]
+        );
+    }
+}
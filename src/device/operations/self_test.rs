@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+
+use crate::Device;
+
+/// Timeout covering the duration of the self-test
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Result of the device self-test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfTestResult {
+    /// All tests passed
+    Passed,
+
+    /// A test failed, with the error code reported by the device
+    ///
+    /// The code is passed through as is, its bit assignment is not mapped to explanations.
+    Failed(i32),
+}
+
+impl SelfTestResult {
+    /// Check if the self-test passed
+    pub fn passed(&self) -> bool {
+        *self == Self::Passed
+    }
+}
+
+impl Device {
+    /// Self-test: run the internal self-test and get its result
+    ///
+    /// The IO timeout is extended to at least 30 seconds while waiting for the result.
+    ///
+    /// ADC command: `*TST?`
+    pub fn self_test(&mut self) -> Result<SelfTestResult> {
+        let timeout = self.timeout();
+        self.set_timeout(timeout.max(SELF_TEST_TIMEOUT));
+
+        let response = self.write("*TST?").and_then(|_| self.read());
+        self.set_timeout(timeout);
+
+        let response = response?;
+        let trimmed = response.trim();
+        let numeric_part = trimmed.strip_prefix("*TST").unwrap_or(trimmed).trim();
+        let code: i32 = numeric_part
+            .parse()
+            .map_err(|e| anyhow!("Failed to parse self-test result '{}': {}", response, e))?;
+
+        Ok(match code {
+            0 => SelfTestResult::Passed,
+            code => SelfTestResult::Failed(code),
+        })
+    }
+}
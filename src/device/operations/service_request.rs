@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, anyhow};
+
+use crate::{Device, StatusByte};
+
+/// Interval between status byte polls while waiting for a service request
+const SRQ_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+impl Device {
+    /// Service request enable: get the mask of status bits that raise a service request
+    ///
+    /// ADC command: `*SRE?`
+    pub fn service_request_enable(&mut self) -> Result<StatusByte> {
+        self.write("*SRE?")?;
+        let response = self.read()?;
+        let trimmed = response.trim();
+        let numeric_part = trimmed.strip_prefix("*SRE").unwrap_or(trimmed).trim();
+        let mask: u8 = numeric_part.parse().map_err(|e| {
+            anyhow!(
+                "Failed to parse service request enable value '{}': {}",
+                response,
+                e
+            )
+        })?;
+        Ok(StatusByte::from_bits_retain(mask))
+    }
+
+    /// Service request enable: set the mask of status bits that raise a service request
+    ///
+    /// The RQS bit itself cannot be enabled and is ignored by the device.
+    ///
+    /// ADC command: `*SRE<mask>`
+    pub fn service_request_enable_set(&mut self, mask: StatusByte) -> Result<()> {
+        let mask = mask.difference(StatusByte::RQS);
+
+        // Set the mask
+        self.write(&format!("*SRE{}", mask.bits()))?;
+
+        // Verify the mask
+        if self.service_request_enable()? != mask {
+            return Err(anyhow!("Failed to set service request enable"));
+        }
+
+        Ok(())
+    }
+
+    /// Service request: wait until the device requests service
+    ///
+    /// Polls the status byte instead of data reads, so no response data is consumed while
+    /// waiting. Fails if no service request is raised within `timeout`.
+    pub fn wait_for_srq(&mut self, timeout: Duration) -> Result<StatusByte> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let status = self.status()?;
+            if status.service_requested() {
+                return Ok(status);
+            }
+
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "No service request within {} ms",
+                    timeout.as_millis()
+                ));
+            }
+
+            std::thread::sleep(SRQ_POLL_INTERVAL);
+        }
+    }
+
+    /// Service request: wait until the device requests service
+    #[deprecated(note = "use `wait_for_srq` instead")]
+    pub fn wait_srq(&mut self, timeout: Duration) -> Result<StatusByte> {
+        self.wait_for_srq(timeout)
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use bitflags::bitflags;
+
+use crate::Device;
+
+bitflags! {
+    /// Status byte of the device
+    ///
+    /// Only the bits defined by IEEE 488.2 are named. The instrument specific bits (such as
+    /// error and measurement end) are kept as read and available through
+    /// [`StatusByte::instrument_bits`], until their assignment is confirmed against the
+    /// instrument manual.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct StatusByte: u8 {
+        /// Message available: a response is waiting in the output queue
+        const MAV = 0x10;
+
+        /// Event status bit: an enabled event of the standard event status register occurred
+        const ESB = 0x20;
+
+        /// Request service: the device raises a service request
+        const RQS = 0x40;
+
+        // Keep instrument specific bits
+        const _ = !0;
+    }
+}
+
+impl StatusByte {
+    /// Check if a response can be read
+    pub fn message_available(&self) -> bool {
+        self.contains(Self::MAV)
+    }
+
+    /// Get the instrument specific bits, i.e. all bits not defined by IEEE 488.2
+    pub fn instrument_bits(&self) -> u8 {
+        self.bits() & !(Self::MAV | Self::ESB | Self::RQS).bits()
+    }
+
+    /// Check if the device requests service
+    pub fn service_requested(&self) -> bool {
+        self.contains(Self::RQS)
+    }
+}
+
+impl Device {
+    /// Status: read the status byte of the device
+    ///
+    /// Read with a control transfer, so the response data in the output queue is untouched.
+    pub fn status(&self) -> Result<StatusByte> {
+        Ok(StatusByte::from_bits_retain(self.read_status_byte()?))
+    }
+}
@@ -1,10 +1,11 @@
+use adcmt_7351_controller_macros::adc_command;
 use anyhow::{Result, anyhow};
 use num_derive::FromPrimitive;
-use num_traits::FromPrimitive;
 
 use crate::Device;
 
 /// Trigger source mapping enum
+#[adc_command(query = "TRS?", set = "TRS{}", response = u8)]
 #[derive(Debug, FromPrimitive, Clone, PartialEq)]
 pub enum TriggerSource {
     /// Immediate trigger
@@ -39,19 +40,7 @@ impl Device {
     ///
     /// ADC command: `INIC?`
     pub fn continuously_measure(&mut self) -> Result<bool> {
-        self.write("INIC?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("INIC").unwrap_or(trimmed);
-        let num: u8 = numeric_part.parse().map_err(|e| {
-            anyhow!(
-                "Failed to parse continuously measure value '{}': {}",
-                response,
-                e
-            )
-        })?;
-
-        Ok(num == 1)
+        self.query_parsed("INIC?")
     }
 
     /// Continuously measure: enable continuous measurement
@@ -82,43 +71,11 @@ impl Device {
         Ok(())
     }
 
-    /// Trigger source: get current trigger source
-    ///
-    /// ADC command: `TRS?`
-    pub fn trigger_source(&mut self) -> Result<TriggerSource> {
-        self.write("TRS?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("TRS").unwrap_or(trimmed);
-        let num: u8 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse trigger source value '{}': {}", response, e))?;
-
-        TriggerSource::from_u8(num)
-            .ok_or_else(|| anyhow!("Failed to convert trigger source value to TriggerSource"))
-    }
-
-    /// Trigger source: set current trigger source
-    ///
-    /// ADC command: `TRS<trigger_source>`
-    pub fn trigger_source_set(&mut self, trigger_source: TriggerSource) -> Result<()> {
-        // Set the trigger source
-        self.write(&format!("TRS{}", trigger_source.clone() as u8))?;
-
-        // Verify the trigger source
-        if self.trigger_source()? != trigger_source {
-            return Err(anyhow!("Failed to set trigger source"));
-        }
-
-        Ok(())
-    }
-
     /// Trigger delay: get current trigger delay
     ///
     /// ADC command: `TRD?`
     pub fn trigger_delay(&mut self) -> Result<String> {
-        self.write("TRD?")?;
-        self.read()
+        self.query_parsed("TRD?")
     }
 
     /// Trigger delay: set current trigger delay
@@ -140,14 +97,7 @@ impl Device {
     ///
     /// ADC command: `SPN?`
     pub fn sampling_count(&mut self) -> Result<u16> {
-        self.write("SPN?")?;
-        let response = self.read()?;
-        let trimmed = response.trim();
-        let numeric_part = trimmed.strip_prefix("SPN").unwrap_or(trimmed);
-        let num: u16 = numeric_part
-            .parse()
-            .map_err(|e| anyhow!("Failed to parse sampling count value '{}': {}", response, e))?;
-        Ok(num)
+        self.query_parsed("SPN?")
     }
 
     /// Sampling count: set current sampling count
@@ -0,0 +1,190 @@
+//! Runtime registry of typed response parsers
+
+use std::any::Any;
+
+use anyhow::{Result, anyhow};
+use num_traits::FromPrimitive;
+
+use crate::{
+    AutoZero, Device, FunctionCode, NumberOfDisplayDigits, RawRange, SamplingRate, TriggerSource,
+};
+
+/// Type-erased parser function
+type ParserFn = Box<dyn Fn(&str) -> Result<Box<dyn Any + Send>> + Send>;
+
+/// Registry mapping command prefixes to typed response parsers
+///
+/// The typed getters of `Device` (e.g. `function()`, `sampling_rate()`) parse their responses
+/// through this registry. Users can add typed parsing for commands the crate does not know
+/// about, e.g. ones found in the manual but not wrapped yet, without forking the crate.
+pub struct ResponseParsers {
+    parsers: Vec<(String, ParserFn)>,
+}
+
+impl ResponseParsers {
+    /// Create a registry with the parsers used by the built-in getters
+    pub fn new() -> Self {
+        let mut parsers = Self::empty();
+
+        parsers.register("F", |response| enumerated::<FunctionCode>(response, "F"));
+        parsers.register("R", |response| enumerated::<RawRange>(response, "R"));
+        parsers.register("PR", |response| enumerated::<SamplingRate>(response, "PR"));
+        parsers.register("RE", |response| {
+            enumerated::<NumberOfDisplayDigits>(response, "RE")
+        });
+        parsers.register("AZ", |response| enumerated::<AutoZero>(response, "AZ"));
+        parsers.register("TRS", |response| {
+            enumerated::<TriggerSource>(response, "TRS")
+        });
+        parsers.register("INIC", |response| {
+            numeric::<u8>(response, "INIC").map(|value| value == 1)
+        });
+        parsers.register("SPN", |response| numeric::<u16>(response, "SPN"));
+
+        // Returned as sent by the instrument, without header
+        for prefix in ["TRD", "KOM"] {
+            parsers.register(prefix, |response| Ok(response.to_string()));
+        }
+
+        parsers
+    }
+
+    /// Create a registry without any parser
+    pub fn empty() -> Self {
+        Self {
+            parsers: Vec::new(),
+        }
+    }
+
+    /// Register a parser for responses to commands starting with `prefix`
+    ///
+    /// Replaces a parser previously registered for the same prefix. Replacing a built-in
+    /// parser with one of a different type makes the matching typed getter fail.
+    pub fn register<T, F>(&mut self, prefix: &str, parser: F)
+    where
+        T: Any + Send,
+        F: Fn(&str) -> Result<T> + Send + 'static,
+    {
+        let parser: ParserFn =
+            Box::new(move |response| parser(response).map(|value| Box::new(value) as _));
+
+        self.parsers.retain(|(existing, _)| existing != prefix);
+        self.parsers.push((prefix.to_string(), parser));
+    }
+
+    /// Parse the response to a command with the parser of the longest matching prefix
+    pub fn parse<T: Any>(&self, command: &str, response: &str) -> Result<T> {
+        let (prefix, parser) = self
+            .parsers
+            .iter()
+            .filter(|(prefix, _)| command_matches(command, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .ok_or_else(|| anyhow!("No response parser registered for '{}'", command))?;
+
+        let value = parser(response)?;
+        value.downcast::<T>().map(|value| *value).map_err(|_| {
+            anyhow!(
+                "Response parser for '{}' produces a different type than requested",
+                prefix
+            )
+        })
+    }
+}
+
+impl Default for ResponseParsers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Device {
+    /// Parsers: get the response parser registry of this device
+    pub fn parsers_mut(&mut self) -> &mut ResponseParsers {
+        &mut self.parsers
+    }
+
+    /// Parsers: send a query and parse the response with the registered parser
+    pub fn query_parsed<T: Any>(&mut self, command: &str) -> Result<T> {
+        self.write(command)?;
+        let response = self.read()?;
+        self.parsers.parse(command, &response)
+    }
+}
+
+/// Strip the echoed header from a response, e.g. `PR2` with header `PR` gives `2`
+pub fn strip_header<'a>(response: &'a str, header: &str) -> &'a str {
+    let trimmed = response.trim();
+    trimmed.strip_prefix(header).unwrap_or(trimmed)
+}
+
+/// Internal function: Check if a command belongs to a prefix
+///
+/// The prefix must be followed by a non-letter, so `R` does not match `RE?`.
+fn command_matches(command: &str, prefix: &str) -> bool {
+    command.starts_with(prefix)
+        && !command[prefix.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+/// Parse a response into a code enum after stripping its header, e.g. `F1` into
+/// `FunctionCode::DCV`
+pub fn enumerated<T: FromPrimitive>(response: &str, header: &str) -> Result<T> {
+    let code: i64 = numeric(response, header)?;
+
+    T::from_i64(code).ok_or_else(|| {
+        anyhow!(
+            "Unknown '{}' code {} in response '{}'",
+            header,
+            code,
+            response
+        )
+    })
+}
+
+/// Internal function: Parse a numeric response after stripping its header
+fn numeric<T>(response: &str, header: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    strip_header(response, header).parse().map_err(|e| {
+        anyhow!(
+            "Failed to parse '{}' response '{}': {}",
+            header,
+            response,
+            e
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Device, FunctionCode, SamplingRate, SimulatedDevice};
+
+    #[test]
+    fn built_in_parsers_match_getter_types() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+
+        assert_eq!(
+            device.query_parsed::<FunctionCode>("F?").unwrap(),
+            FunctionCode::DCV
+        );
+        assert_eq!(
+            device.query_parsed::<SamplingRate>("PR?").unwrap(),
+            SamplingRate::MEDIUM
+        );
+        assert!(device.query_parsed::<bool>("INIC?").unwrap());
+        assert_eq!(device.query_parsed::<String>("TRD?").unwrap(), "0");
+        assert_eq!(device.query_parsed::<String>("KOM?").unwrap(), "10");
+    }
+
+    #[test]
+    fn getters_parse_through_registry() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+        device.parsers_mut().register("SPN", |_| anyhow::Ok(42u16));
+
+        assert_eq!(device.sampling_count().unwrap(), 42);
+    }
+}
@@ -0,0 +1,146 @@
+//! Command family allow/deny policy
+
+use std::{collections::HashSet, fmt};
+
+/// Family of instrument commands, used for policy decisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandFamily {
+    /// Measurement function selection (`F`, `INH?`)
+    Function,
+
+    /// Measurement range selection (`R`, `RX`)
+    Range,
+
+    /// Sampling rate (`PR`)
+    SamplingRate,
+
+    /// Number of display digits (`RE`)
+    DisplayDigits,
+
+    /// Auto zero (`AZ`)
+    AutoZero,
+
+    /// Trigger and measurement control (`INI`, `INIC`, `ABO`, `TRS`, `TRD`, `SPN`, `*TRG`)
+    Trigger,
+
+    /// Continuity threshold (`KOM`)
+    Continuity,
+
+    /// Device reset (`*RST`)
+    Reset,
+
+    /// Calibration (`*CAL`)
+    Calibration,
+
+    /// Panel setting save and recall (`*SAV`, `*RCL`)
+    PanelSave,
+
+    /// Other IEEE 488.2 common commands (`*IDN?`, `*CLS`, `*STB?`, ...)
+    Common,
+
+    /// Any command not covered by another family
+    Other,
+}
+
+impl CommandFamily {
+    /// Classify a single command part (no `,` separators) by its mnemonic
+    pub fn classify(command: &str) -> Self {
+        let command = command.trim();
+        let common = command.starts_with('*');
+        let mnemonic: String = command
+            .trim_start_matches('*')
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        match (common, mnemonic.as_str()) {
+            (false, "F" | "INH") => Self::Function,
+            (false, "R" | "RX") => Self::Range,
+            (false, "PR") => Self::SamplingRate,
+            (false, "RE") => Self::DisplayDigits,
+            (false, "AZ") => Self::AutoZero,
+            (false, "INI" | "INIC" | "ABO" | "TRS" | "TRD" | "SPN") | (true, "TRG") => {
+                Self::Trigger
+            }
+            (false, "KOM") => Self::Continuity,
+            (true, "RST") => Self::Reset,
+            (true, "CAL") => Self::Calibration,
+            (true, "SAV" | "RCL") => Self::PanelSave,
+            (true, _) => Self::Common,
+            (false, _) => Self::Other,
+        }
+    }
+}
+
+/// Policy restricting which command families a device handle may send
+#[derive(Debug, Clone, Default)]
+pub enum CommandPolicy {
+    /// Every command is permitted
+    #[default]
+    AllowAll,
+
+    /// Only the listed families are permitted
+    AllowOnly(HashSet<CommandFamily>),
+
+    /// Every family except the listed ones is permitted
+    Deny(HashSet<CommandFamily>),
+}
+
+impl CommandPolicy {
+    /// Permit only the given families
+    pub fn allow_only(families: impl IntoIterator<Item = CommandFamily>) -> Self {
+        Self::AllowOnly(families.into_iter().collect())
+    }
+
+    /// Forbid the given families
+    pub fn deny(families: impl IntoIterator<Item = CommandFamily>) -> Self {
+        Self::Deny(families.into_iter().collect())
+    }
+
+    /// Check if a command family is permitted
+    pub fn permits(&self, family: CommandFamily) -> bool {
+        match self {
+            Self::AllowAll => true,
+            Self::AllowOnly(families) => families.contains(&family),
+            Self::Deny(families) => !families.contains(&family),
+        }
+    }
+
+    /// Check a full command, returning the first forbidden family if any
+    pub(crate) fn check(&self, command: &str) -> Result<(), PolicyError> {
+        for part in command.split([',', ';']) {
+            let family = CommandFamily::classify(part);
+            if !self.permits(family) {
+                return Err(PolicyError {
+                    command: command.to_string(),
+                    family,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A command was rejected by the command policy of the device handle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyError {
+    /// The rejected command
+    pub command: String,
+
+    /// The forbidden family the command belongs to
+    pub family: CommandFamily,
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Command '{}' is rejected by policy: {:?} commands are not permitted",
+            self.command, self.family
+        )
+    }
+}
+
+impl std::error::Error for PolicyError {}
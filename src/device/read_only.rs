@@ -0,0 +1,107 @@
+//! Read-only device handle support
+
+use std::fmt;
+
+use crate::CommandFamily;
+
+/// Families rejected by a read-only handle even in their query form (e.g. `*CAL?` starts a
+/// calibration)
+const STATE_CHANGING_FAMILIES: [CommandFamily; 4] = [
+    CommandFamily::Calibration,
+    CommandFamily::Reset,
+    CommandFamily::PanelSave,
+    CommandFamily::Trigger,
+];
+
+/// Trigger settings whose query form only reads the configuration (e.g. `TRS?`), unlike
+/// the trigger actions `INI`, `ABO` and `*TRG`
+const TRIGGER_SETTINGS: [&str; 4] = ["TRS", "TRD", "SPN", "INIC"];
+
+/// A state-changing command was rejected by a read-only device handle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyError {
+    /// The rejected command
+    pub command: String,
+}
+
+impl fmt::Display for ReadOnlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Command '{}' changes the device state and is rejected by a read-only handle",
+            self.command
+        )
+    }
+}
+
+impl std::error::Error for ReadOnlyError {}
+
+/// Check if a command only queries the device
+///
+/// Every part must have a `?` right after its mnemonic (e.g. `F?`, `INH?1`, `*IDN?`), so
+/// `F1?` still counts as setting the function. Anything else is treated as state-changing.
+pub(crate) fn is_query(command: &str) -> bool {
+    command.split([',', ';']).all(is_query_part)
+}
+
+/// Check if a read-only handle may send a command
+///
+/// Besides being a query, no part may belong to a family that changes the device state
+/// regardless of its form (calibration, reset, panel save and trigger). Queries of the
+/// trigger settings are the exception, they do not start or stop a measurement.
+pub(crate) fn is_read_only_safe(command: &str) -> bool {
+    command.split([',', ';']).all(|part| {
+        let state_changing = STATE_CHANGING_FAMILIES.contains(&CommandFamily::classify(part))
+            && !is_trigger_setting(part);
+        !state_changing && is_query_part(part)
+    })
+}
+
+/// Internal function: Check if a command part addresses a trigger setting
+fn is_trigger_setting(part: &str) -> bool {
+    let part = part.trim();
+    let mnemonic = part
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or(part);
+
+    TRIGGER_SETTINGS
+        .iter()
+        .any(|setting| mnemonic.eq_ignore_ascii_case(setting))
+}
+
+/// Internal function: Check if a single command part is a query of its mnemonic
+fn is_query_part(part: &str) -> bool {
+    part.trim()
+        .trim_start_matches('*')
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+        .starts_with('?')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Device, ReadOnlyError, SimulatedDevice};
+
+    #[test]
+    fn read_only_handle_rejects_state_changing_commands() {
+        let mut device = Device::from_transport_read_only(Box::new(SimulatedDevice::new()));
+
+        for command in ["*CAL?", "*RST", "*TRG", "INI", "F1?", "F1", "F?;R3"] {
+            let error = device.write(command).unwrap_err();
+            assert!(
+                error.downcast_ref::<ReadOnlyError>().is_some(),
+                "'{}' was not rejected",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn read_only_handle_permits_queries() {
+        let mut device = Device::from_transport_read_only(Box::new(SimulatedDevice::new()));
+
+        for command in ["F?", "R?", "*IDN?", "INH?1", "F?,R?", "TRS?", "INIC?"] {
+            device.write(command).unwrap();
+        }
+    }
+}
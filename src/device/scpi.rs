@@ -0,0 +1,448 @@
+//! Translation of standard SCPI commands onto the native ADC command set
+
+use anyhow::{Result, anyhow};
+
+use crate::{Device, FunctionCode, strip_header};
+
+/// Native commands equivalent to a SCPI command
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScpiTranslation {
+    /// Native ADC commands to write, in order
+    pub commands: Vec<String>,
+
+    /// Whether a response should be read after writing the commands
+    pub query: bool,
+
+    /// How the native response is converted back to SCPI form
+    pub reply: ScpiReply,
+}
+
+/// Conversion of a native response back to SCPI form
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScpiReply {
+    /// The response is already in SCPI form (measurement data, common commands)
+    Unchanged,
+
+    /// `TRS<code>` to the trigger source keyword, e.g. `IMM`
+    TriggerSource,
+
+    /// `SPN<count>` to the sample count
+    SampleCount,
+
+    /// `R<code>` to the full scale of the range of the function, `AUTO` for auto range
+    Range(FunctionCode),
+}
+
+impl ScpiTranslation {
+    fn write(commands: Vec<String>) -> Self {
+        Self {
+            commands,
+            query: false,
+            reply: ScpiReply::Unchanged,
+        }
+    }
+
+    fn query(commands: Vec<String>) -> Self {
+        Self::query_with(commands, ScpiReply::Unchanged)
+    }
+
+    fn query_with(commands: Vec<String>, reply: ScpiReply) -> Self {
+        Self {
+            commands,
+            query: true,
+            reply,
+        }
+    }
+
+    /// Convert the native response of the translated query back to SCPI form
+    pub fn translate_reply(&self, response: &str) -> Result<String> {
+        let response = response.trim();
+
+        match &self.reply {
+            ScpiReply::Unchanged => Ok(response.to_string()),
+            ScpiReply::TriggerSource => {
+                let code: u8 = native_code(response, "TRS")?;
+                let source = match code {
+                    0 => "IMM",
+                    1 => "MAN",
+                    2 => "EXT",
+                    3 => "BUS",
+                    _ => return Err(anyhow!("Unknown trigger source code {}", code)),
+                };
+                Ok(source.to_string())
+            }
+            ScpiReply::SampleCount => Ok(native_code::<u16>(response, "SPN")?.to_string()),
+            ScpiReply::Range(function) => {
+                let code: u8 = native_code(response, "R")?;
+                if code == 0 {
+                    return Ok("AUTO".to_string());
+                }
+
+                range_table(function.clone())?
+                    .iter()
+                    .find(|(_, range)| *range == code)
+                    .map(|(full_scale, _)| full_scale.to_string())
+                    .ok_or_else(|| {
+                        anyhow!("Unknown range code {} for function {:?}", code, function)
+                    })
+            }
+        }
+    }
+}
+
+/// Translate a single SCPI command into native ADC commands
+///
+/// Supported subset:
+/// - IEEE 488.2 common commands (`*RST`, `*IDN?`, `*CLS`, `*TST?`, `*TRG`, ...), passed through
+/// - `CONFigure:<function> [<range>]` and `MEASure:<function>? [<range>]`
+/// - `[SENSe:]<function>:RANGe <range>` and `[SENSe:]<function>:RANGe:AUTO ON`
+/// - `READ?`, `FETCh?`, `INITiate`, `ABORt`
+/// - `TRIGger:SOURce IMMediate|BUS|EXTernal|MANual`, `SAMPle:COUNt <n>`
+///
+/// Functions are `VOLTage[:DC]`, `VOLTage:AC`, `CURRent[:DC]`, `CURRent:AC`, `RESistance`,
+/// `FREQuency`, `DIODe` and `CONTinuity`. Ranges are a numeric value (the smallest range
+/// covering it is selected), `AUTO`, `DEF`, `MIN` or `MAX`. A resolution after the range
+/// (e.g. `CONF:VOLT:DC 20,0.001`) is accepted and ignored. The argument of `FREQuency` is the
+/// expected frequency, which needs no range selection and is ignored as well.
+///
+/// Responses of `TRIGger:SOURce?`, `SAMPle:COUNt?` and `<function>:RANGe?` are converted
+/// back to SCPI form with [`ScpiTranslation::translate_reply`].
+pub fn translate_scpi(command: &str) -> Result<ScpiTranslation> {
+    let command = command.trim();
+    let command = command.strip_prefix(':').unwrap_or(command);
+
+    if command.is_empty() {
+        return Err(anyhow!("SCPI command cannot be empty"));
+    }
+
+    // Common commands are understood by the instrument as-is
+    if command.starts_with('*') {
+        let query = command.ends_with('?');
+        return Ok(ScpiTranslation {
+            commands: vec![command.to_uppercase()],
+            query,
+            reply: ScpiReply::Unchanged,
+        });
+    }
+
+    let (header, argument) = match command.split_once(char::is_whitespace) {
+        Some((header, argument)) => (header, Some(argument.trim())),
+        None => (command, None),
+    };
+    let argument = argument.filter(|argument| !argument.is_empty());
+
+    let query = header.ends_with('?');
+    let header = header.trim_end_matches('?');
+    let nodes: Vec<&str> = header.split(':').collect();
+
+    let unsupported = || anyhow!("Unsupported SCPI command '{}'", command);
+
+    match nodes.as_slice() {
+        [root] if keyword(root, "READ") && query => {
+            Ok(ScpiTranslation::query(vec!["INI".to_string()]))
+        }
+        [root] if keyword(root, "FETCh") && query => Ok(ScpiTranslation::query(Vec::new())),
+        [root] if keyword(root, "INITiate") && !query => {
+            Ok(ScpiTranslation::write(vec!["INI".to_string()]))
+        }
+        [root, imm] if keyword(root, "INITiate") && keyword(imm, "IMMediate") && !query => {
+            Ok(ScpiTranslation::write(vec!["INI".to_string()]))
+        }
+        [root] if keyword(root, "ABORt") && !query => {
+            Ok(ScpiTranslation::write(vec!["ABO".to_string()]))
+        }
+        [root, rest @ ..] if keyword(root, "CONFigure") && !query => {
+            let function = parse_function(rest).ok_or_else(unsupported)?;
+            Ok(ScpiTranslation::write(vec![configure_command(
+                function,
+                range_argument(argument),
+            )?]))
+        }
+        [root, rest @ ..] if keyword(root, "MEASure") && query => {
+            let function = parse_function(rest).ok_or_else(unsupported)?;
+            Ok(ScpiTranslation::query(vec![
+                configure_command(function, range_argument(argument))?,
+                "INI".to_string(),
+            ]))
+        }
+        [root, source] if keyword(root, "TRIGger") && keyword(source, "SOURce") => {
+            if query {
+                return Ok(ScpiTranslation::query_with(
+                    vec!["TRS?".to_string()],
+                    ScpiReply::TriggerSource,
+                ));
+            }
+
+            let argument = argument.ok_or_else(|| anyhow!("Missing trigger source"))?;
+            let code = if keyword(argument, "IMMediate") {
+                0
+            } else if keyword(argument, "MANual") {
+                1
+            } else if keyword(argument, "EXTernal") {
+                2
+            } else if keyword(argument, "BUS") {
+                3
+            } else {
+                return Err(anyhow!("Unsupported trigger source '{}'", argument));
+            };
+
+            Ok(ScpiTranslation::write(vec![format!("TRS{}", code)]))
+        }
+        [root, count] if keyword(root, "SAMPle") && keyword(count, "COUNt") => {
+            if query {
+                return Ok(ScpiTranslation::query_with(
+                    vec!["SPN?".to_string()],
+                    ScpiReply::SampleCount,
+                ));
+            }
+
+            let argument = argument.ok_or_else(|| anyhow!("Missing sample count"))?;
+            let count: u16 = argument
+                .parse()
+                .map_err(|e| anyhow!("Failed to parse sample count '{}': {}", argument, e))?;
+
+            Ok(ScpiTranslation::write(vec![format!("SPN{}", count)]))
+        }
+        _ => {
+            // [SENSe:]<function>:RANGe[:AUTO]
+            let nodes = match nodes.split_first() {
+                Some((first, rest)) if keyword(first, "SENSe") => rest,
+                _ => nodes.as_slice(),
+            };
+
+            let range_at = nodes
+                .iter()
+                .position(|node| keyword(node, "RANGe"))
+                .ok_or_else(unsupported)?;
+            let function = parse_function(&nodes[..range_at]).ok_or_else(unsupported)?;
+
+            if query {
+                return Ok(ScpiTranslation::query_with(
+                    vec!["R?".to_string()],
+                    ScpiReply::Range(function),
+                ));
+            }
+
+            match &nodes[range_at + 1..] {
+                [] => {
+                    let argument =
+                        range_argument(argument).ok_or_else(|| anyhow!("Missing range value"))?;
+                    let range = range_code(function, argument)?;
+                    Ok(ScpiTranslation::write(vec![format!("R{}", range)]))
+                }
+                [auto] if keyword(auto, "AUTO") => {
+                    let enabled = argument.is_none_or(|arg| {
+                        arg == "1" || arg.eq_ignore_ascii_case("ON") || keyword(arg, "ONCE")
+                    });
+                    // Disabling auto range keeps the current range fixed
+                    let native = if enabled { "R0" } else { "RX" };
+                    Ok(ScpiTranslation::write(vec![native.to_string()]))
+                }
+                _ => Err(unsupported()),
+            }
+        }
+    }
+}
+
+impl Device {
+    /// SCPI: execute a SCPI command through the native command set
+    ///
+    /// Several commands may be joined with `;`, each is treated as starting from the root.
+    /// Returns the response of the last query, if any, converted back to SCPI form.
+    pub fn scpi(&mut self, command: &str) -> Result<Option<String>> {
+        let mut response = None;
+
+        for part in command.split(';').filter(|part| !part.trim().is_empty()) {
+            let translation = translate_scpi(part)?;
+
+            for native in &translation.commands {
+                self.write(native)?;
+            }
+
+            if translation.query {
+                let native = self.read()?;
+                response = Some(translation.translate_reply(&native)?);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// Internal function: Match a SCPI keyword in short or long form, case-insensitively
+///
+/// The short form is the uppercase prefix of `spec`, e.g. `VOLT` for `VOLTage`.
+fn keyword(token: &str, spec: &str) -> bool {
+    let short_len = spec
+        .find(|c: char| c.is_ascii_lowercase())
+        .unwrap_or(spec.len());
+
+    token.eq_ignore_ascii_case(&spec[..short_len]) || token.eq_ignore_ascii_case(spec)
+}
+
+/// Internal function: Parse the function nodes of a SCPI header
+fn parse_function(nodes: &[&str]) -> Option<FunctionCode> {
+    match nodes {
+        [volt] if keyword(volt, "VOLTage") => Some(FunctionCode::DCV),
+        [volt, dc] if keyword(volt, "VOLTage") && dc.eq_ignore_ascii_case("DC") => {
+            Some(FunctionCode::DCV)
+        }
+        [volt, ac] if keyword(volt, "VOLTage") && ac.eq_ignore_ascii_case("AC") => {
+            Some(FunctionCode::ACV)
+        }
+        [curr] if keyword(curr, "CURRent") => Some(FunctionCode::DCI),
+        [curr, dc] if keyword(curr, "CURRent") && dc.eq_ignore_ascii_case("DC") => {
+            Some(FunctionCode::DCI)
+        }
+        [curr, ac] if keyword(curr, "CURRent") && ac.eq_ignore_ascii_case("AC") => {
+            Some(FunctionCode::ACI)
+        }
+        [res] if keyword(res, "RESistance") => Some(FunctionCode::Resistance),
+        [freq] if keyword(freq, "FREQuency") => Some(FunctionCode::Frequency),
+        [diode] if keyword(diode, "DIODe") => Some(FunctionCode::Diode),
+        [cont] if keyword(cont, "CONTinuity") => Some(FunctionCode::Continuity),
+        _ => None,
+    }
+}
+
+/// Internal function: Range part of a `<range>[,<resolution>]` argument
+fn range_argument(argument: Option<&str>) -> Option<&str> {
+    argument
+        .and_then(|argument| argument.split(',').next())
+        .map(str::trim)
+        .filter(|range| !range.is_empty())
+}
+
+/// Internal function: Parse the native code of a response after stripping its header
+fn native_code<T>(response: &str, header: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    strip_header(response, header).parse().map_err(|e| {
+        anyhow!(
+            "Failed to parse '{}' response '{}': {}",
+            header,
+            response,
+            e
+        )
+    })
+}
+
+/// Internal function: Native command configuring a function and optional range
+fn configure_command(function: FunctionCode, range: Option<&str>) -> Result<String> {
+    let function_code = function.clone() as u8;
+
+    match function {
+        // No range selection for these functions
+        FunctionCode::Diode | FunctionCode::Continuity => Ok(format!("F{}", function_code)),
+        // The argument of frequency is the expected frequency, not a range
+        FunctionCode::Frequency => Ok(format!("F{}", function_code)),
+        _ => {
+            let range = range_code(function, range.unwrap_or("AUTO"))?;
+            Ok(format!("F{},R{}", function_code, range))
+        }
+    }
+}
+
+/// Internal function: Full scale of each range of a function, paired with its native code
+fn range_table(function: FunctionCode) -> Result<&'static [(f64, u8)]> {
+    let ranges: &'static [(f64, u8)] = match function {
+        FunctionCode::DCV => &[(0.2, 3), (2.0, 4), (20.0, 5), (200.0, 6), (1000.0, 7)],
+        FunctionCode::ACV | FunctionCode::ACVCoupling | FunctionCode::Frequency => {
+            &[(0.2, 3), (2.0, 4), (20.0, 5), (200.0, 6), (700.0, 7)]
+        }
+        FunctionCode::DCI | FunctionCode::ACI | FunctionCode::ACICoupling => {
+            &[(0.2, 6), (2.0, 7), (10.0, 8)]
+        }
+        FunctionCode::Resistance => &[
+            (200.0, 3),
+            (2e3, 4),
+            (20e3, 5),
+            (200e3, 6),
+            (2e6, 7),
+            (20e6, 8),
+            (200e6, 9),
+        ],
+        FunctionCode::ResistanceLowPower => &[
+            (200.0, 3),
+            (2e3, 4),
+            (20e3, 5),
+            (200e3, 6),
+            (2e6, 7),
+            (20e6, 8),
+        ],
+        FunctionCode::Diode | FunctionCode::Continuity => {
+            return Err(anyhow!("Function {:?} has no range", function));
+        }
+    };
+
+    Ok(ranges)
+}
+
+/// Internal function: Map a SCPI range argument onto the native range code of a function
+pub(crate) fn range_code(function: FunctionCode, argument: &str) -> Result<u8> {
+    let ranges = range_table(function.clone())?;
+
+    let has_auto = function != FunctionCode::Frequency;
+
+    if keyword(argument, "AUTO") || keyword(argument, "DEFault") {
+        if has_auto {
+            return Ok(0);
+        }
+        return Err(anyhow!("Function {:?} has no auto range", function));
+    }
+
+    if keyword(argument, "MINimum") {
+        return Ok(ranges[0].1);
+    }
+
+    if keyword(argument, "MAXimum") {
+        return Ok(ranges[ranges.len() - 1].1);
+    }
+
+    let value: f64 = argument
+        .parse()
+        .map_err(|e| anyhow!("Failed to parse range value '{}': {}", argument, e))?;
+
+    ranges
+        .iter()
+        .find(|(full_scale, _)| value.abs() <= *full_scale)
+        .map(|(_, code)| *code)
+        .ok_or_else(|| anyhow!("Range value {} exceeds the maximum range", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::translate_scpi;
+    use crate::{Device, SimulatedDevice};
+
+    #[test]
+    fn configure_accepts_range_and_resolution() {
+        let translation = translate_scpi("CONF:VOLT:DC 20,0.001").unwrap();
+        assert_eq!(translation.commands, ["F1,R5"]);
+    }
+
+    #[test]
+    fn configure_frequency_ignores_expected_frequency() {
+        let translation = translate_scpi("CONF:FREQ 1000").unwrap();
+        assert_eq!(translation.commands, ["F50"]);
+    }
+
+    #[test]
+    fn query_replies_are_translated_back() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+
+        device.scpi("TRIG:SOUR BUS").unwrap();
+        assert_eq!(device.scpi("TRIG:SOUR?").unwrap().as_deref(), Some("BUS"));
+
+        device.scpi("SAMP:COUN 5").unwrap();
+        assert_eq!(device.scpi("SAMP:COUN?").unwrap().as_deref(), Some("5"));
+
+        device.scpi("CONF:VOLT:DC 20").unwrap();
+        assert_eq!(device.scpi("VOLT:RANG?").unwrap().as_deref(), Some("20"));
+
+        device.scpi("VOLT:RANG:AUTO ON").unwrap();
+        assert_eq!(device.scpi("VOLT:RANG?").unwrap().as_deref(), Some("AUTO"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Snapshot of the front panel state
+
+use std::time::SystemTime;
+
+use anyhow::Result;
+
+use crate::{
+    AutoZero, Device, FunctionCode, NumberOfDisplayDigits, RawRange, Reading, SamplingRate,
+    TriggerSource,
+    device::{audit::format_timestamp, commands::json_string},
+};
+
+/// Software "photo of the front panel": reading, annunciators and settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplaySnapshot {
+    /// When the snapshot was taken
+    pub taken_at: SystemTime,
+
+    /// Reading shown on the display, with its unit and overload annunciator
+    pub reading: Reading,
+
+    /// Measurement function
+    pub function: FunctionCode,
+
+    /// Measurement range
+    pub range: RawRange,
+
+    /// Auto range annunciator
+    pub auto_range: bool,
+
+    /// Sampling rate
+    pub sampling_rate: SamplingRate,
+
+    /// Number of display digits
+    pub display_digits: NumberOfDisplayDigits,
+
+    /// Auto zero setting
+    pub auto_zero: AutoZero,
+
+    /// Trigger source
+    pub trigger_source: TriggerSource,
+}
+
+impl DisplaySnapshot {
+    /// Serialize the snapshot as a JSON object, enums by their variant name
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"taken_at\":{},\"reading\":{{\"value\":{},\"unit\":{},\"overload\":{}}},\"function\":{},\"range\":{},\"auto_range\":{},\"sampling_rate\":{},\"display_digits\":{},\"auto_zero\":{},\"trigger_source\":{}}}",
+            format_timestamp(self.taken_at),
+            if self.reading.value.is_finite() {
+                self.reading.value.to_string()
+            } else {
+                "null".to_string()
+            },
+            json_string(&format!("{:?}", self.reading.unit)),
+            self.reading.overload,
+            json_string(&format!("{:?}", self.function)),
+            json_string(&format!("{:?}", self.range)),
+            self.auto_range,
+            json_string(&format!("{:?}", self.sampling_rate)),
+            json_string(&format!("{:?}", self.display_digits)),
+            json_string(&format!("{:?}", self.auto_zero)),
+            json_string(&format!("{:?}", self.trigger_source)),
+        )
+    }
+}
+
+impl Device {
+    /// Snapshot: capture the reading and settings shown on the front panel
+    ///
+    /// The reading is the last measurement, queried without triggering a new one, so a
+    /// snapshot works on a read-only handle and leaves the instrument state untouched.
+    /// `FETC?` is not listed among the native ADC commands of the manual; it is assumed to
+    /// return the last measurement data as the SCPI `FETCh?` query does.
+    ///
+    /// ADC command: `FETC?`
+    pub fn snapshot_display(&mut self) -> Result<DisplaySnapshot> {
+        let function = self.function()?;
+        let range = self.range()?;
+        let sampling_rate = self.sampling_rate()?;
+        let display_digits = self.number_of_display_digits()?;
+        let auto_zero = self.auto_zero()?;
+        let trigger_source = self.trigger_source()?;
+        self.write("FETC?")?;
+        let reading = self.fetch()?;
+
+        Ok(DisplaySnapshot {
+            taken_at: SystemTime::now(),
+            reading,
+            auto_range: range == RawRange::AUTO,
+            function,
+            range,
+            sampling_rate,
+            display_digits,
+            auto_zero,
+            trigger_source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Device, SimulatedDevice};
+
+    #[test]
+    fn snapshot_works_on_read_only_handle() {
+        let mut device = Device::from_transport_read_only(Box::new(SimulatedDevice::new()));
+
+        let snapshot = device.snapshot_display().unwrap();
+        assert!(snapshot.reading.value.is_finite());
+        assert!(!snapshot.reading.overload);
+        assert!(
+            snapshot
+                .to_json()
+                .contains("\"unit\":\"Volt\",\"overload\":false")
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Observable device state kept up to date in the background
+
+use std::{
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, RecvTimeoutError, Sender, channel},
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{Device, Dmm, FunctionCode, RawRange, Reading, SamplingRate};
+
+/// Connection status of the observed device
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionStatus {
+    /// The last refresh succeeded
+    Connected,
+
+    /// The last refresh failed, with the error message
+    Error(String),
+}
+
+/// Snapshot of the observed device state
+///
+/// Fields are `None` until they have been read successfully once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceState {
+    /// Current measurement function
+    pub function: Option<FunctionCode>,
+
+    /// Current measurement range
+    pub range: Option<RawRange>,
+
+    /// Current sampling rate
+    pub sampling_rate: Option<SamplingRate>,
+
+    /// Last reading with unit and overload flag, only taken if readings are enabled on the
+    /// refresher
+    pub last_reading: Option<Reading>,
+
+    /// Connection status
+    pub connection: ConnectionStatus,
+}
+
+impl Default for DeviceState {
+    fn default() -> Self {
+        Self {
+            function: None,
+            range: None,
+            sampling_rate: None,
+            last_reading: None,
+            connection: ConnectionStatus::Connected,
+        }
+    }
+}
+
+/// Shared state between the refresher handle and its thread
+#[derive(Default)]
+struct Shared {
+    state: DeviceState,
+    subscribers: Vec<Sender<DeviceState>>,
+}
+
+/// Background refresher owning a device and keeping a `DeviceState` up to date
+///
+/// Intended for GUI frontends, which can bind to the state snapshot and redraw on change
+/// notifications instead of talking to the device themselves.
+pub struct StateRefresher {
+    shared: Arc<Mutex<Shared>>,
+    stop: Sender<()>,
+    thread: JoinHandle<Device>,
+}
+
+impl StateRefresher {
+    /// Move the device into a background thread refreshing its state every `interval`
+    ///
+    /// With `take_readings`, each refresh also triggers a measurement for `last_reading`.
+    pub fn spawn(mut device: Device, interval: Duration, take_readings: bool) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let (stop, stop_receiver) = channel();
+
+        let thread_shared = Arc::clone(&shared);
+        let thread = std::thread::spawn(move || {
+            loop {
+                let previous = lock(&thread_shared).state.clone();
+                let state = Self::refresh(&mut device, previous.clone(), take_readings);
+
+                if state != previous {
+                    let mut shared = lock(&thread_shared);
+                    shared.state = state.clone();
+                    shared
+                        .subscribers
+                        .retain(|subscriber| subscriber.send(state.clone()).is_ok());
+                }
+
+                match stop_receiver.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+
+            device
+        });
+
+        Self {
+            shared,
+            stop,
+            thread,
+        }
+    }
+
+    /// Get a snapshot of the current state
+    pub fn state(&self) -> DeviceState {
+        lock(&self.shared).state.clone()
+    }
+
+    /// Subscribe to state changes, each change delivers the new snapshot
+    pub fn changes(&self) -> Receiver<DeviceState> {
+        let (sender, receiver) = channel();
+        lock(&self.shared).subscribers.push(sender);
+        receiver
+    }
+
+    /// Stop refreshing and get the device back
+    pub fn stop(self) -> Result<Device> {
+        // The thread may already be gone, joining reports that
+        let _ = self.stop.send(());
+        self.thread
+            .join()
+            .map_err(|_| anyhow!("State refresher thread panicked"))
+    }
+
+    /// Internal method: Read the state from the device, keeping old values on failure
+    fn refresh(device: &mut Device, mut state: DeviceState, take_readings: bool) -> DeviceState {
+        let result = (|| -> Result<()> {
+            state.function = Some(device.function()?);
+            state.range = Some(device.range()?);
+            state.sampling_rate = Some(device.sampling_rate()?);
+            if take_readings {
+                state.last_reading = Some(device.read_value()?);
+            }
+            Ok(())
+        })();
+
+        state.connection = match result {
+            Ok(()) => ConnectionStatus::Connected,
+            Err(e) => ConnectionStatus::Error(format!("{:#}", e)),
+        };
+
+        state
+    }
+}
+
+/// Internal function: Lock the shared state, recovering from a poisoned lock
+fn lock(shared: &Mutex<Shared>) -> std::sync::MutexGuard<'_, Shared> {
+    shared
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
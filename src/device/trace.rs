@@ -0,0 +1,134 @@
+//! Human-readable annotation of command traces
+
+use num_traits::FromPrimitive;
+
+use crate::{
+    AuditEntry, AutoZero, FunctionCode, NumberOfDisplayDigits, RawRange, SamplingRate,
+    TriggerSource, device::audit::format_timestamp,
+};
+
+/// Describe the meaning of a command, decoding parameters through the typed layer
+///
+/// Commands joined with `,` or `;` are described part by part, separated by `; `.
+pub fn annotate_command(command: &str) -> String {
+    command
+        .split([',', ';'])
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| annotate_part(part.trim()))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Export audit entries as an annotated listing, one command per line
+///
+/// Line format: `<unix seconds>.<millis> [<tag>] <command>  # <meaning> (<result>)`
+pub fn export_trace(entries: &[AuditEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let result = match &entry.result {
+                Ok(()) => "ok".to_string(),
+                Err(e) => format!("error: {}", e),
+            };
+
+            format!(
+                "{} [{}] {}  # {} ({})\n",
+                format_timestamp(entry.timestamp),
+                entry.tag.as_deref().unwrap_or("-"),
+                entry.command,
+                annotate_command(&entry.command),
+                result
+            )
+        })
+        .collect()
+}
+
+/// Internal function: Describe a single command part
+fn annotate_part(part: &str) -> String {
+    let upper = part.to_ascii_uppercase();
+    let mnemonic_len = upper
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_alphabetic() || (i == 0 && c == '*')))
+        .map(|(i, _)| i)
+        .unwrap_or(upper.len());
+    let (mnemonic, parameter) = upper.split_at(mnemonic_len);
+
+    let query = parameter == "?";
+    let code = parameter.parse::<u16>().ok();
+
+    // Decode a numeric parameter into a typed enum, falling back to the raw value
+    fn typed<T: FromPrimitive + std::fmt::Debug>(code: Option<u16>, raw: &str) -> String {
+        code.and_then(|code| T::from_u16(code))
+            .map(|value| format!("{:?}", value))
+            .unwrap_or_else(|| format!("unknown value '{}'", raw))
+    }
+
+    match (mnemonic, query) {
+        ("F", true) => "Function: query current measurement mode".to_string(),
+        ("F", false) => format!(
+            "Function: set to {}",
+            typed::<FunctionCode>(code, parameter)
+        ),
+        ("INH", _) => {
+            let function = parameter.trim_start_matches('?');
+            let code = function.parse::<u16>().ok();
+            format!(
+                "Function: check if {} is ready",
+                typed::<FunctionCode>(code, function)
+            )
+        }
+        ("R", true) => "Range: query current range".to_string(),
+        ("R", false) => format!("Range: set to {}", typed::<RawRange>(code, parameter)),
+        ("RX", _) => "Range: fix automatic range".to_string(),
+        ("PR", true) => "Sampling rate: query".to_string(),
+        ("PR", false) => format!(
+            "Sampling rate: set to {}",
+            typed::<SamplingRate>(code, parameter)
+        ),
+        ("RE", true) => "Number of display digits: query".to_string(),
+        ("RE", false) => format!(
+            "Number of display digits: set to {}",
+            typed::<NumberOfDisplayDigits>(code, parameter)
+        ),
+        ("AZ", true) => "Auto zero: query".to_string(),
+        ("AZ", false) => format!("Auto zero: set to {}", typed::<AutoZero>(code, parameter)),
+        ("INI", _) => "Start: leave the IDLE state".to_string(),
+        ("ABO", _) => "Abort: enter the IDLE state".to_string(),
+        ("INIC", true) => "Continuous measurement: query".to_string(),
+        ("INIC", false) => match code {
+            Some(1) => "Continuous measurement: enable".to_string(),
+            Some(0) => "Continuous measurement: disable".to_string(),
+            _ => format!("Continuous measurement: unknown value '{}'", parameter),
+        },
+        ("TRS", true) => "Trigger source: query".to_string(),
+        ("TRS", false) => format!(
+            "Trigger source: set to {}",
+            typed::<TriggerSource>(code, parameter)
+        ),
+        ("TRD", true) => "Trigger delay: query".to_string(),
+        ("TRD", false) => format!("Trigger delay: set to {}", parameter),
+        ("SPN", true) => "Sampling count: query".to_string(),
+        ("SPN", false) => format!("Sampling count: set to {}", parameter),
+        ("KOM", true) => "Continuity threshold constant: query".to_string(),
+        ("KOM", false) => format!("Continuity threshold constant: set to {}", parameter),
+        ("*RST", _) => "Reset: restore the initial settings".to_string(),
+        ("*IDN", _) => "Identify: query the identity string".to_string(),
+        ("*TST", _) => "Self test: run and query the result".to_string(),
+        ("*CLS", _) => "Clear status: clear the status registers".to_string(),
+        ("*TRG", _) => "Bus trigger".to_string(),
+        _ => "Unknown command".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::annotate_command;
+
+    #[test]
+    fn compound_commands_are_annotated_part_by_part() {
+        let comma = annotate_command("F1,R3");
+        assert_eq!(annotate_command("F1;R3"), comma);
+        assert_eq!(annotate_command("F1; R3;"), comma);
+        assert_eq!(comma.split("; ").count(), 2);
+    }
+}
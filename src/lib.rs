@@ -5,8 +5,16 @@
 
 mod device;
 mod protocol;
+
+// Low-level USB transport is only public with the `raw-transport` feature
+#[cfg(feature = "raw-transport")]
+pub mod transport;
+#[cfg(not(feature = "raw-transport"))]
 mod transport;
 
 // Re-exports
 pub use device::*;
-pub use transport::UsbDeviceMetadata;
+pub use transport::{
+    OpenOptions, PermissionError, PermissionHint, SimulatedDevice, TcpTransport, Transport,
+    UsbDeviceMetadata,
+};
@@ -4,6 +4,9 @@ use anyhow::{Ok, Result};
 
 use crate::protocol::MAX_CMD_LEN;
 
+/// USBTMC bmTransferAttributes bit marking the last transfer of a message
+const USBTMC_EOM: u8 = 0x01;
+
 /// Packet encoder and decoder
 pub struct Packet;
 
@@ -76,13 +79,140 @@ impl Packet {
         packet
     }
 
+    /// Encode a write request into a USBTMC `DEV_DEP_MSG_OUT` packet
+    ///
+    /// Unlike [`Packet::encode_write`], TransferSize holds the message length without the
+    /// alignment padding, as the USBTMC specification requires.
+    pub fn encode_write_usbtmc(command_str: &str, sequence: u8) -> Result<Vec<u8>> {
+        let mut packet = Self::encode_write(command_str, sequence)?;
+
+        // TransferSize: command + newline
+        let transfer_size = (command_str.len() + 1) as u32;
+        packet[4..8].copy_from_slice(&transfer_size.to_le_bytes());
+
+        Ok(packet)
+    }
+
+    /// Encode a USBTMC `REQUEST_DEV_DEP_MSG_IN` packet asking for up to `max_len` bytes
+    pub fn encode_read_usbtmc(sequence: u8, max_len: u32) -> Vec<u8> {
+        let mut packet = Self::encode_read(sequence);
+
+        // TransferSize: maximum number of message bytes to send
+        packet[4..8].copy_from_slice(&max_len.to_le_bytes());
+
+        packet
+    }
+
+    /// Decode a USBTMC `DEV_DEP_MSG_IN` response packet
+    ///
+    /// Checks the MsgID and that bTag/bTagInverse echo the request `sequence`, and takes
+    /// exactly TransferSize bytes of data. Returns the data and whether the EOM bit is set.
+    pub fn decode_read_usbtmc(buffer: &[u8], sequence: u8) -> Result<(Vec<u8>, bool)> {
+        if buffer.len() < 12 {
+            anyhow::bail!(
+                "Response too short for a USBTMC header ({} bytes)",
+                buffer.len()
+            );
+        }
+
+        if buffer[0] != 0x02 {
+            anyhow::bail!("Unexpected USBTMC MsgID {} in response", buffer[0]);
+        }
+
+        if buffer[2] != !buffer[1] {
+            anyhow::bail!("Corrupted USBTMC header: bTagInverse does not match bTag");
+        }
+
+        if buffer[1] != sequence {
+            anyhow::bail!(
+                "Response bTag {} does not match request bTag {}",
+                buffer[1],
+                sequence
+            );
+        }
+
+        let transfer_size =
+            u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+        if 12 + transfer_size > buffer.len() {
+            anyhow::bail!(
+                "Response TransferSize {} exceeds the {} received data bytes",
+                transfer_size,
+                buffer.len() - 12
+            );
+        }
+
+        let eom = buffer[8] & USBTMC_EOM != 0;
+
+        Ok((buffer[12..12 + transfer_size].to_vec(), eom))
+    }
+
+    /// Decode the command of a write request packet
+    pub fn decode_write(buffer: &[u8]) -> Result<Vec<u8>> {
+        if buffer.len() < 12 || buffer[0] != 0x01 {
+            anyhow::bail!("Not a write request packet");
+        }
+
+        // Extract upper header
+        let data_len = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+        let data = &buffer[12..(12 + data_len).min(buffer.len())];
+
+        // Command ends at the appended newline, the rest is alignment padding
+        let end = data.iter().position(|&b| b == 0x0A).unwrap_or(data.len());
+
+        Ok(data[..end].to_vec())
+    }
+
+    /// Encode a read response packet, as sent by the device
+    pub fn encode_read_response(data: &[u8], sequence: u8) -> Vec<u8> {
+        // Lower 32 bits of header: 02 [seq] [~seq] 00
+        let lower_header = ((!sequence as u32 & 0xFF) << 16) | ((sequence as u32 & 0xFF) << 8) | 2;
+
+        // Upper 32 bits of header: data length
+        let upper_header = data.len() as u32;
+
+        let mut packet = vec![0u8; 12 + data.len()]; // zero value init
+        packet[0..4].copy_from_slice(&lower_header.to_le_bytes());
+        packet[4..8].copy_from_slice(&upper_header.to_le_bytes());
+
+        // Whole message in one transfer
+        packet[8] = USBTMC_EOM;
+        packet[12..].copy_from_slice(data);
+
+        packet
+    }
+
+    /// Get the total length of a read response packet from its header
+    ///
+    /// Returns `None` for raw data without header, whose length is unknown.
+    pub fn read_response_len(buffer: &[u8]) -> Option<usize> {
+        if buffer.len() >= 12 && buffer[0] == 0x02 {
+            let data_len = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
+            Some(12 + data_len as usize)
+        } else {
+            None
+        }
+    }
+
     /// Devode a read response packet
-    pub fn decode_read(buffer: &[u8]) -> Result<Vec<u8>> {
+    ///
+    /// A response with header must echo the `sequence` of the read request, otherwise it is
+    /// a stale response of an earlier request and decoding fails.
+    pub fn decode_read(buffer: &[u8], sequence: u8) -> Result<Vec<u8>> {
         if buffer.is_empty() {
             return Ok(Vec::new());
         }
 
         let (data_start, data_size) = if buffer.len() >= 12 && buffer[0] == 0x02 {
+            // Check the echoed sequence
+            if buffer[1] != sequence || buffer[2] != !sequence {
+                anyhow::bail!(
+                    "Response sequence {} does not match request sequence {}, \
+                     clear the device to discard stale responses",
+                    buffer[1],
+                    sequence
+                );
+            }
+
             // Extract upper header
             let response_data_len =
                 u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
@@ -115,3 +245,90 @@ impl Packet {
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::SequenceCounter;
+
+    #[test]
+    fn usbtmc_write_carries_exact_transfer_size_and_tag() {
+        let packet = Packet::encode_write_usbtmc("F?", 7).unwrap();
+
+        assert_eq!(&packet[0..4], &[0x01, 7, !7, 0x00]);
+        assert_eq!(u32::from_le_bytes(packet[4..8].try_into().unwrap()), 3);
+        assert_eq!(packet[8] & USBTMC_EOM, USBTMC_EOM);
+        assert_eq!(&packet[12..15], b"F?\n");
+        assert_eq!(packet.len() % 4, 0);
+    }
+
+    #[test]
+    fn usbtmc_tag_wraps_around_skipping_zero() {
+        let counter = SequenceCounter::new();
+        for _ in 0..254 {
+            counter.next();
+        }
+        assert_eq!(counter.next(), 255);
+
+        let tag = counter.next();
+        assert_eq!(tag, 1);
+
+        let request = Packet::encode_read_usbtmc(tag, 116);
+        assert_eq!(&request[0..4], &[0x02, 1, 0xFE, 0x00]);
+        assert_eq!(u32::from_le_bytes(request[4..8].try_into().unwrap()), 116);
+    }
+
+    #[test]
+    fn usbtmc_read_honors_transfer_size_and_eom() {
+        let mut response = Packet::encode_read_response(b"F1\r\n", 9);
+        response.extend_from_slice(&[0xAA; 4]); // alignment padding
+
+        let (data, eom) = Packet::decode_read_usbtmc(&response, 9).unwrap();
+        assert_eq!(data, b"F1\r\n");
+        assert!(eom);
+
+        response[8] = 0x00;
+        let (_, eom) = Packet::decode_read_usbtmc(&response, 9).unwrap();
+        assert!(!eom);
+    }
+
+    #[test]
+    fn usbtmc_read_rejects_wrong_tag() {
+        let response = Packet::encode_read_response(b"F1", 3);
+        assert!(Packet::decode_read_usbtmc(&response, 4).is_err());
+
+        let mut corrupted = response.clone();
+        corrupted[2] = 0x00;
+        assert!(Packet::decode_read_usbtmc(&corrupted, 3).is_err());
+    }
+
+    #[test]
+    fn usbtmc_read_rejects_truncated_packets() {
+        let response = Packet::encode_read_response(b"F1\r\n", 5);
+
+        assert!(Packet::decode_read_usbtmc(&response[..8], 5).is_err());
+        assert!(Packet::decode_read_usbtmc(&response[..14], 5).is_err());
+    }
+
+    #[test]
+    fn read_accepts_matching_sequence() {
+        let response = Packet::encode_read_response(b"F1\r\n", 42);
+        assert_eq!(Packet::decode_read(&response, 42).unwrap(), b"F1");
+    }
+
+    #[test]
+    fn read_rejects_stale_sequence() {
+        let response = Packet::encode_read_response(b"F1\r\n", 41);
+        assert!(Packet::decode_read(&response, 42).is_err());
+
+        let mut corrupted = Packet::encode_read_response(b"F1\r\n", 42);
+        corrupted[2] = 42;
+        assert!(Packet::decode_read(&corrupted, 42).is_err());
+    }
+
+    #[test]
+    fn read_treats_truncated_header_as_raw_data() {
+        assert_eq!(Packet::read_response_len(&[0x02, 1, 0xFE, 0x00]), None);
+        assert_eq!(Packet::decode_read(b"F1\r\n", 1).unwrap(), b"F1");
+    }
+}
@@ -32,6 +32,11 @@ impl SequenceCounter {
         next
     }
 
+    /// Reset counter, the next sequence number is 1 again
+    pub fn reset(&self) {
+        self.counter.set(0);
+    }
+
     /// Increment counter
     #[allow(unused)]
     pub fn increment(&self) {
@@ -0,0 +1,125 @@
+//! Diagnostics about who holds a USB interface
+
+use rusb::{Context as RUsbContext, Device, DeviceHandle};
+
+/// Describe the holders of a busy interface, as far as they are detectable
+pub fn describe_interface_holders(
+    handle: &DeviceHandle<RUsbContext>,
+    device: &Device<RUsbContext>,
+    interface_number: u8,
+    config_value: u8,
+) -> String {
+    let mut holders = Vec::new();
+
+    if let Ok(true) = handle.kernel_driver_active(interface_number) {
+        match kernel_driver_name(device, interface_number, config_value) {
+            Some(name) => holders.push(format!("kernel driver '{}'", name)),
+            None => holders.push("a kernel driver".to_string()),
+        }
+    }
+
+    for (pid, name) in processes_holding(device) {
+        holders.push(format!("process {} ({})", pid, name));
+    }
+
+    if holders.is_empty() {
+        "holder could not be detected".to_string()
+    } else {
+        format!("held by {}", holders.join(", "))
+    }
+}
+
+/// Name of the kernel driver bound to the interface, read from sysfs
+#[cfg(target_os = "linux")]
+fn kernel_driver_name(
+    device: &Device<RUsbContext>,
+    interface_number: u8,
+    config_value: u8,
+) -> Option<String> {
+    let ports = device.port_numbers().ok()?;
+    if ports.is_empty() {
+        return None;
+    }
+
+    let port_path = ports
+        .iter()
+        .map(|port| port.to_string())
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let driver_link = format!(
+        "/sys/bus/usb/devices/{}-{}:{}.{}/driver",
+        device.bus_number(),
+        port_path,
+        config_value,
+        interface_number
+    );
+
+    let target = std::fs::read_link(driver_link).ok()?;
+    target
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_driver_name(
+    _device: &Device<RUsbContext>,
+    _interface_number: u8,
+    _config_value: u8,
+) -> Option<String> {
+    None
+}
+
+/// Other processes having the device node open, found by scanning procfs
+#[cfg(target_os = "linux")]
+fn processes_holding(device: &Device<RUsbContext>) -> Vec<(u32, String)> {
+    let node = format!(
+        "/dev/bus/usb/{:03}/{:03}",
+        device.bus_number(),
+        device.address()
+    );
+    let own_pid = std::process::id();
+
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == own_pid {
+            continue;
+        }
+
+        // Permission errors are expected for processes of other users
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        let holds_node = fds.flatten().any(|fd| {
+            std::fs::read_link(fd.path())
+                .map(|target| target.as_os_str() == node.as_str())
+                .unwrap_or(false)
+        });
+
+        if holds_node {
+            let name = std::fs::read_to_string(entry.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            found.push((pid, name));
+        }
+    }
+
+    found
+}
+
+#[cfg(not(target_os = "linux"))]
+fn processes_holding(_device: &Device<RUsbContext>) -> Vec<(u32, String)> {
+    Vec::new()
+}
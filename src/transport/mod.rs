@@ -1,5 +1,14 @@
 //! USB transport layer for USB device communication
 
+use std::time::Duration;
+
+use anyhow::Result;
+
+mod claim_diagnostics;
+mod open_options;
+mod permission_error;
+mod simulated_device;
+mod tcp_transport;
 mod usb_context;
 mod usb_device;
 mod usb_device_metadata;
@@ -11,6 +20,43 @@ pub const VID: u16 = 0x1334;
 pub const PID: u16 = 0x0203;
 
 // Re-exports
+pub use open_options::OpenOptions;
+pub use permission_error::{PermissionError, PermissionHint};
+pub use simulated_device::SimulatedDevice;
+pub use tcp_transport::TcpTransport;
 pub use usb_context::UsbContext;
 pub use usb_device::UsbDevice;
 pub use usb_device_metadata::UsbDeviceMetadata;
+
+/// Link carrying encoded packets between a device and the instrument
+///
+/// Implemented by the USB device, [`TcpTransport`] and [`SimulatedDevice`]; alternative links
+/// can be plugged into a device with `Device::from_transport`.
+pub trait Transport: Send {
+    /// Write raw data, returning the number of bytes transferred
+    fn write(&self, data: &[u8]) -> Result<usize>;
+
+    /// Read raw data into the buffer, returning the number of bytes transferred
+    fn read(&self, buffer: &mut [u8]) -> Result<usize>;
+
+    /// Set timeout for all operations
+    fn set_timeout(&mut self, timeout: Duration);
+
+    /// Get timeout used in current operation
+    fn timeout(&self) -> Duration;
+
+    /// Clear input/output buffers of the link
+    fn clear_halt(&self) -> Result<()>;
+
+    /// Read the status byte of the instrument
+    fn read_status(&self) -> Result<u8>;
+
+    /// Set delay after every write giving the instrument time to process
+    fn set_write_delay(&mut self, delay: Duration);
+
+    /// Get delay after every write
+    fn write_delay(&self) -> Duration;
+
+    /// Max packet size of the read link, a transfer shorter than it ends a response
+    fn max_packet_size(&self) -> usize;
+}
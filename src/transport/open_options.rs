@@ -0,0 +1,74 @@
+//! Options controlling how a USB device is opened
+
+use std::time::Duration;
+
+/// Options used when opening a USB device
+#[derive(Debug, Clone)]
+pub struct OpenOptions {
+    /// Total time to keep retrying when the interface is busy
+    pub(crate) claim_timeout: Duration,
+
+    /// Delay between two claim attempts
+    pub(crate) claim_retry_interval: Duration,
+
+    /// Detach an attached kernel driver (e.g. `usbtmc`) before claiming
+    pub(crate) detach_kernel_driver: bool,
+
+    /// Delay after every write giving the instrument time to process
+    pub(crate) write_delay: Duration,
+
+    /// Wait for responses by polling the status byte instead of fixed delays
+    pub(crate) status_polling: bool,
+}
+
+impl OpenOptions {
+    /// Create options with default values (single claim attempt, keep kernel driver, 20 ms
+    /// write delay, no status polling)
+    pub fn new() -> Self {
+        Self {
+            claim_timeout: Duration::ZERO,
+            claim_retry_interval: Duration::from_millis(200),
+            detach_kernel_driver: false,
+            write_delay: Duration::from_millis(20),
+            status_polling: false,
+        }
+    }
+
+    /// Keep retrying to claim a busy interface until the timeout elapses
+    pub fn claim_timeout(mut self, timeout: Duration) -> Self {
+        self.claim_timeout = timeout;
+        self
+    }
+
+    /// Set the delay between two claim attempts
+    pub fn claim_retry_interval(mut self, interval: Duration) -> Self {
+        self.claim_retry_interval = interval;
+        self
+    }
+
+    /// Detach an attached kernel driver before claiming the interface
+    pub fn detach_kernel_driver(mut self, detach: bool) -> Self {
+        self.detach_kernel_driver = detach;
+        self
+    }
+
+    /// Set the delay after every write, skipped while the device polls the status byte
+    pub fn write_delay(mut self, delay: Duration) -> Self {
+        self.write_delay = delay;
+        self
+    }
+
+    /// Wait for responses by polling the status byte, for devices raising MAV reliably
+    ///
+    /// See `Device::set_status_polling`.
+    pub fn status_polling(mut self, enabled: bool) -> Self {
+        self.status_polling = enabled;
+        self
+    }
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
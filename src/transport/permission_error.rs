@@ -0,0 +1,108 @@
+//! Structured error for permission failures when opening a device
+
+use std::fmt;
+
+use rusb::{Context as RUsbContext, Device};
+
+use crate::transport::{PID, VID};
+
+/// Platform-specific hint on how to grant access to the device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionHint {
+    /// A udev rule granting access is missing (Linux)
+    UdevRule {
+        /// Rule to install, e.g. into `/etc/udev/rules.d/99-adcmt-7351.rules`
+        rule: String,
+    },
+
+    /// The WinUSB driver must be bound to the device, e.g. with Zadig (Windows)
+    WinUsbDriver,
+
+    /// No specific hint for this platform
+    Unknown,
+}
+
+impl PermissionHint {
+    /// Hint for the current platform
+    pub fn for_current_platform() -> Self {
+        if cfg!(target_os = "linux") {
+            Self::UdevRule {
+                rule: format!(
+                    "SUBSYSTEM==\"usb\", ATTRS{{idVendor}}==\"{:04x}\", ATTRS{{idProduct}}==\"{:04x}\", MODE=\"0666\"",
+                    VID, PID
+                ),
+            }
+        } else if cfg!(target_os = "windows") {
+            Self::WinUsbDriver
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl fmt::Display for PermissionHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UdevRule { rule } => write!(f, "install the udev rule '{}' and replug", rule),
+            Self::WinUsbDriver => {
+                write!(f, "bind the WinUSB driver to the device, e.g. with Zadig")
+            }
+            Self::Unknown => write!(f, "check the permissions of the current user"),
+        }
+    }
+}
+
+/// Access to the USB device was denied by the operating system
+#[derive(Debug, Clone)]
+pub struct PermissionError {
+    /// USB bus number of the device
+    pub bus_number: u8,
+
+    /// USB address of the device on its bus
+    pub address: u8,
+
+    /// How to grant access on the current platform
+    pub hint: PermissionHint,
+
+    source: rusb::Error,
+}
+
+impl PermissionError {
+    /// Create a permission error for the device at the given bus and address
+    pub(crate) fn new(bus_number: u8, address: u8, source: rusb::Error) -> Self {
+        Self {
+            bus_number,
+            address,
+            hint: PermissionHint::for_current_platform(),
+            source,
+        }
+    }
+}
+
+/// Convert an error of opening a device, turning access failures into a `PermissionError`
+pub(crate) fn open_error(device: &Device<RUsbContext>, error: rusb::Error) -> anyhow::Error {
+    match error {
+        rusb::Error::Access => anyhow::Error::new(PermissionError::new(
+            device.bus_number(),
+            device.address(),
+            error,
+        )),
+        error => error.into(),
+    }
+}
+
+impl fmt::Display for PermissionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Permission denied for USB device at bus {:03} address {:03}: {}",
+            self.bus_number, self.address, self.hint
+        )
+    }
+}
+
+impl std::error::Error for PermissionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
@@ -0,0 +1,291 @@
+//! Simulated instrument for hardware-free operation
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+
+use anyhow::{Ok, Result, anyhow};
+
+use crate::{protocol::Packet, transport::Transport};
+
+/// Max packet size of the simulated full-speed bulk endpoint
+const MAX_PACKET_SIZE: usize = 64;
+
+/// Identity reported by the simulated instrument
+const IDENTITY: &str = "ADCMT,7351A,SIMULATED,1.00";
+
+/// Settings reported without their header (as the real instrument does)
+const HEADERLESS: [&str; 2] = ["TRD", "KOM"];
+
+/// Status byte bit set while a response is waiting to be read
+const MESSAGE_AVAILABLE: u8 = 0x10;
+
+/// Status byte bit set while a service request is raised
+const REQUEST_SERVICE: u8 = 0x40;
+
+/// Mutable state of the simulated instrument
+struct SimulatedState {
+    settings: BTreeMap<String, String>,
+    responses: VecDeque<String>,
+    outgoing: Vec<u8>,
+    read_sequence: u8,
+    conversions: u64,
+    last_measurement: Option<String>,
+    service_request_enable: u8,
+}
+
+impl SimulatedState {
+    /// Internal function: Settings after power-on or `*RST`
+    fn default_settings() -> BTreeMap<String, String> {
+        [
+            ("F", "1"),
+            ("R", "0"),
+            ("PR", "2"),
+            ("RE", "5"),
+            ("AZ", "1"),
+            ("TRS", "0"),
+            ("SPN", "1"),
+            ("INIC", "1"),
+            ("TRD", "0"),
+            ("KOM", "10"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+    }
+
+    /// Internal method: Execute a single command
+    fn execute(&mut self, command: &str) -> Result<()> {
+        match command {
+            "*IDN?" => self.responses.push_back(IDENTITY.to_string()),
+            "*TST?" => self.responses.push_back("0".to_string()),
+            "*STB?" => self.responses.push_back("0".to_string()),
+            "*RST" => {
+                self.settings = Self::default_settings();
+                self.responses.clear();
+            }
+            "*SRE?" => self
+                .responses
+                .push_back(self.service_request_enable.to_string()),
+            "INI" => {
+                let measurement = self.measurement();
+                self.responses.push_back(measurement);
+            }
+            "FETC?" => {
+                let measurement = match &self.last_measurement {
+                    Some(measurement) => measurement.clone(),
+                    None => self.measurement(),
+                };
+                self.responses.push_back(measurement);
+            }
+            "*CLS" | "ABO" => {}
+            "RX" => {
+                self.settings.insert("R".to_string(), "5".to_string());
+            }
+            _ if command.starts_with("INH?") => self.responses.push_back("0".to_string()),
+            _ if command.starts_with("*SRE") => {
+                self.service_request_enable = command["*SRE".len()..]
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid service request enable '{}': {}", command, e))?;
+            }
+            _ => {
+                let header_len = command
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(command.len());
+                let (header, rest) = command.split_at(header_len);
+
+                if !self.settings.contains_key(header) {
+                    return Err(anyhow!(
+                        "Simulated device does not support command '{}'",
+                        command
+                    ));
+                }
+
+                if rest == "?" {
+                    let value = &self.settings[header];
+                    let response = if HEADERLESS.contains(&header) {
+                        value.clone()
+                    } else {
+                        format!("{}{}", header, value)
+                    };
+                    self.responses.push_back(response);
+                } else {
+                    self.settings.insert(header.to_string(), rest.to_string());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Internal method: Produce the next measurement data of the active function
+    fn measurement(&mut self) -> String {
+        let nominal = match self.settings["F"].as_str() {
+            "3" | "20" => 1.0e3,
+            "5" | "6" | "8" => 1.0e-3,
+            "13" => 0.6,
+            "22" => 10.0,
+            "50" => 1.0e3,
+            _ => 1.0,
+        };
+
+        // Deterministic noise of a few counts in the last digits
+        self.conversions += 1;
+        let noise = ((self.conversions % 7) as f64 - 3.0) * 1e-5;
+
+        let measurement = format!("{:+.5E}", nominal * (1.0 + noise));
+        self.last_measurement = Some(measurement.clone());
+        measurement
+    }
+}
+
+/// Simulated instrument implementing the transport
+///
+/// Understands the ADC command subset used by this crate, keeps settings across commands
+/// and answers queries like the instrument does. Reading without a pending query response
+/// returns simulated measurement data of the active function.
+pub struct SimulatedDevice {
+    state: RefCell<SimulatedState>,
+    timeout: Duration,
+    write_delay: Duration,
+}
+
+impl SimulatedDevice {
+    /// Serial number reported by the simulated instrument in its `*IDN?` response
+    pub const SERIAL_NUMBER: &str = "SIMULATED";
+
+    /// Create a simulated instrument in its power-on state
+    pub fn new() -> Self {
+        Self {
+            state: RefCell::new(SimulatedState {
+                settings: SimulatedState::default_settings(),
+                responses: VecDeque::new(),
+                outgoing: Vec::new(),
+                read_sequence: 0,
+                conversions: 0,
+                last_measurement: None,
+                service_request_enable: 0,
+            }),
+            timeout: Duration::from_secs(5),
+            write_delay: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for SimulatedDevice {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for SimulatedDevice {
+    fn write(&self, data: &[u8]) -> Result<usize> {
+        if data.len() < 12 {
+            return Err(anyhow!("Packet too short ({} bytes)", data.len()));
+        }
+
+        let mut state = self.state.borrow_mut();
+        match data[0] {
+            // Command packet
+            0x01 => {
+                let command = Packet::decode_write(data)?;
+                let command = std::str::from_utf8(&command)
+                    .map_err(|e| anyhow!("Command contains invalid UTF-8 character: {}", e))?;
+
+                for part in command.split([',', ';']).map(str::trim) {
+                    if !part.is_empty() {
+                        state.execute(part)?;
+                    }
+                }
+            }
+            // Read request packet
+            0x02 => state.read_sequence = data[1],
+            kind => return Err(anyhow!("Unknown packet type 0x{:02X}", kind)),
+        }
+
+        // Emulate the processing time of the instrument, if configured
+        if !self.write_delay.is_zero() {
+            std::thread::sleep(self.write_delay);
+        }
+
+        Ok(data.len())
+    }
+
+    fn read(&self, buffer: &mut [u8]) -> Result<usize> {
+        let mut state = self.state.borrow_mut();
+
+        // Continue a response that did not fit into the previous transfer
+        if state.outgoing.is_empty() {
+            let response = match state.responses.pop_front() {
+                Some(response) => response,
+                None => state.measurement(),
+            };
+
+            let data = format!("{}\r\n", response);
+            state.outgoing = Packet::encode_read_response(data.as_bytes(), state.read_sequence);
+        }
+
+        let copied = state.outgoing.len().min(buffer.len());
+        buffer[..copied].copy_from_slice(&state.outgoing[..copied]);
+        state.outgoing.drain(..copied);
+
+        Ok(copied)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn clear_halt(&self) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.responses.clear();
+        state.outgoing.clear();
+        Ok(())
+    }
+
+    fn read_status(&self) -> Result<u8> {
+        let state = self.state.borrow();
+        let mut status = 0x00;
+        if !state.responses.is_empty() || !state.outgoing.is_empty() {
+            status |= MESSAGE_AVAILABLE;
+        }
+        if status & state.service_request_enable != 0 {
+            status |= REQUEST_SERVICE;
+        }
+
+        Ok(status)
+    }
+
+    fn set_write_delay(&mut self, delay: Duration) {
+        self.write_delay = delay
+    }
+
+    fn write_delay(&self) -> Duration {
+        self.write_delay
+    }
+
+    fn max_packet_size(&self) -> usize {
+        MAX_PACKET_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Device, SimulatedDevice};
+
+    #[test]
+    fn compound_commands_split_on_comma_and_semicolon() {
+        let mut device = Device::from_transport(Box::new(SimulatedDevice::new()));
+
+        device.write("F5;R7").unwrap();
+        device.write("F?,R?").unwrap();
+        assert_eq!(device.read().unwrap(), "F5");
+        assert_eq!(device.read().unwrap(), "R7");
+    }
+}
@@ -0,0 +1,186 @@
+//! TCP transport for instruments behind a LAN bridge
+
+use std::{
+    cell::{Cell, RefCell},
+    io::{ErrorKind, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use anyhow::{Context, Ok, Result, anyhow};
+
+use crate::{protocol::Packet, transport::Transport};
+
+/// Packet size reported to the device layer, as for a full-speed bulk endpoint
+const MAX_PACKET_SIZE: usize = 64;
+
+/// Transport talking plain text commands to a LAN bridge (e.g. LAN-to-GPIB or serial)
+///
+/// Commands are sent as text followed by the terminator, responses are read up to the
+/// terminator. The packet framing of the USB link is translated on both ends, so a device
+/// opened with `Device::from_transport` works as over USB.
+pub struct TcpTransport {
+    stream: TcpStream,
+    terminator: String,
+    timeout: Duration,
+    write_delay: Duration,
+    received: RefCell<Vec<u8>>,
+    outgoing: RefCell<Vec<u8>>,
+    read_sequence: Cell<u8>,
+}
+
+impl TcpTransport {
+    /// Connect to a bridge at the given address, terminating commands with LF
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).context("Failed to connect to TCP bridge")?;
+        stream
+            .set_nodelay(true)
+            .context("Failed to disable Nagle's algorithm")?;
+
+        let mut transport = Self {
+            stream,
+            terminator: "\n".to_string(),
+            timeout: Duration::from_secs(5),
+            write_delay: Duration::ZERO,
+            received: RefCell::new(Vec::new()),
+            outgoing: RefCell::new(Vec::new()),
+            read_sequence: Cell::new(0),
+        };
+        transport.set_timeout(transport.timeout);
+
+        Ok(transport)
+    }
+
+    /// Set the terminator of commands and responses
+    ///
+    /// Fails for an empty terminator, as responses could not be delimited.
+    pub fn terminator(mut self, terminator: &str) -> Result<Self> {
+        if terminator.is_empty() {
+            return Err(anyhow!("Terminator of the TCP bridge must not be empty"));
+        }
+
+        self.terminator = terminator.to_string();
+        Ok(self)
+    }
+
+    /// Internal method: Read from the stream until a full response is received
+    fn read_response(&self) -> Result<Vec<u8>> {
+        let terminator = self.terminator.as_bytes();
+        let mut received = self.received.borrow_mut();
+        let mut chunk = [0u8; 256];
+
+        loop {
+            if let Some(position) = received
+                .windows(terminator.len())
+                .position(|window| window == terminator)
+            {
+                let response: Vec<u8> = received.drain(..position + terminator.len()).collect();
+                return Ok(response[..position].to_vec());
+            }
+
+            let count = match (&self.stream).read(&mut chunk) {
+                Result::Ok(0) => return Err(anyhow!("TCP bridge closed the connection")),
+                Result::Ok(count) => count,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(anyhow::Error::new(rusb::Error::Timeout))
+                        .context("Timed out waiting for response from TCP bridge");
+                }
+                Err(e) => return Err(e).context("Failed to read from TCP bridge"),
+            };
+            received.extend_from_slice(&chunk[..count]);
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn write(&self, data: &[u8]) -> Result<usize> {
+        match data.first() {
+            // Command packet
+            Some(0x01) => {
+                let mut command = Packet::decode_write(data)?;
+                command.extend_from_slice(self.terminator.as_bytes());
+                (&self.stream)
+                    .write_all(&command)
+                    .context("Failed to write to TCP bridge")?;
+            }
+            // Read request packet, the bridge answers without a request
+            Some(0x02) if data.len() >= 2 => self.read_sequence.set(data[1]),
+            _ => return Err(anyhow!("Unknown packet for TCP bridge")),
+        }
+
+        if !self.write_delay.is_zero() {
+            std::thread::sleep(self.write_delay);
+        }
+
+        Ok(data.len())
+    }
+
+    fn read(&self, buffer: &mut [u8]) -> Result<usize> {
+        let mut outgoing = self.outgoing.borrow_mut();
+
+        // Continue a response that did not fit into the previous transfer
+        if outgoing.is_empty() {
+            let response = self.read_response()?;
+            *outgoing = Packet::encode_read_response(&response, self.read_sequence.get());
+        }
+
+        let copied = outgoing.len().min(buffer.len());
+        buffer[..copied].copy_from_slice(&outgoing[..copied]);
+        outgoing.drain(..copied);
+
+        Ok(copied)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+
+        // A zero timeout would mean blocking forever for the socket
+        let socket_timeout = Some(timeout.max(Duration::from_millis(1)));
+        let _ = self.stream.set_read_timeout(socket_timeout);
+        let _ = self.stream.set_write_timeout(socket_timeout);
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn clear_halt(&self) -> Result<()> {
+        self.received.borrow_mut().clear();
+        self.outgoing.borrow_mut().clear();
+        Ok(())
+    }
+
+    fn read_status(&self) -> Result<u8> {
+        Err(anyhow!("Status byte is not available over a TCP bridge"))
+    }
+
+    fn set_write_delay(&mut self, delay: Duration) {
+        self.write_delay = delay
+    }
+
+    fn write_delay(&self) -> Duration {
+        self.write_delay
+    }
+
+    fn max_packet_size(&self) -> usize {
+        MAX_PACKET_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::TcpTransport;
+
+    #[test]
+    fn empty_terminator_is_rejected() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+
+        assert!(transport.terminator("").is_err());
+
+        let transport = TcpTransport::connect(listener.local_addr().unwrap()).unwrap();
+        assert_eq!(transport.terminator("\r\n").unwrap().terminator, "\r\n");
+    }
+}
@@ -1,18 +1,33 @@
 //! Low-level fine-grained control of a USB device
 
-use std::time::Duration;
+#[cfg(unix)]
+use std::os::fd::RawFd;
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Ok, Result, anyhow};
-use rusb::{Context as RUsbContext, Device, DeviceHandle, TransferType};
+#[cfg(unix)]
+use rusb::UsbContext as RUsbContextTrait;
+use rusb::{Context as RUsbContext, Device, DeviceHandle, InterfaceDescriptor, TransferType};
 
-use crate::transport::usb_device_metadata::UsbDeviceMetadata;
+use crate::transport::{
+    Transport, claim_diagnostics::describe_interface_holders, open_options::OpenOptions,
+    permission_error::open_error, usb_device_metadata::UsbDeviceMetadata,
+};
 
 /// USB endpoints
 struct UsbEndpoints {
+    config_value: u8,
+    interface_number: u8,
+    setting_number: u8,
     read_addr: u8,
     read_type: u8,
+    read_max_packet_size: usize,
     write_addr: u8,
     write_type: u8,
+    write_max_packet_size: usize,
 }
 
 /// USB device handle with endpoints
@@ -20,29 +35,83 @@ pub struct UsbDevice {
     handle: DeviceHandle<RUsbContext>,
     endpoints: UsbEndpoints,
     timeout: Duration,
+    write_delay: Duration,
+    leftover: RefCell<Vec<u8>>,
 }
 
 impl UsbDevice {
     /// Open a USB device based on metadata
     pub fn open(metadata: &UsbDeviceMetadata) -> Result<Self> {
-        let handle = metadata
-            .device
+        Self::open_with_options(metadata, &OpenOptions::default())
+    }
+
+    /// Open a USB device based on metadata with custom open options
+    pub fn open_with_options(metadata: &UsbDeviceMetadata, options: &OpenOptions) -> Result<Self> {
+        let device = &metadata.device;
+        let handle = device
             .open()
+            .map_err(|e| open_error(device, e))
             .context("Failed to open given USB device")?;
 
-        // Claim the device by claiming the primary interface
-        handle
-            .claim_interface(0)
-            .context("Failed to claim primary interface for given USB device")?;
+        Self::from_handle(handle, options)
+    }
+
+    /// Open a USB device from an already opened file descriptor
+    ///
+    /// Intended for Android, where the USB host API of the platform grants access to the
+    /// device and hands out its file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be a valid usbfs file descriptor, and it must stay open for as long as the
+    /// returned device is alive. The ownership of `fd` is not taken.
+    #[cfg(unix)]
+    pub unsafe fn from_raw_fd(fd: RawFd, options: &OpenOptions) -> Result<Self> {
+        // Android does not allow scanning the USB bus, must be set before context creation
+        #[cfg(target_os = "android")]
+        rusb::disable_device_discovery().context("Failed to disable USB device discovery")?;
+
+        let ctx = RUsbContext::new().context("Failed to create RUSB context")?;
+        let handle = unsafe { ctx.open_device_with_fd(fd) }
+            .context("Failed to open USB device from file descriptor")?;
+
+        Self::from_handle(handle, options)
+    }
+
+    /// Internal method: Finish opening from a device handle
+    fn from_handle(handle: DeviceHandle<RUsbContext>, options: &OpenOptions) -> Result<Self> {
+        let usb_device = handle.device();
 
         // Get all endpoints of current device
-        let endpoints = Self::get_endpoints(&metadata.device)
+        let endpoints = Self::get_endpoints(&usb_device)
             .context("Failed to get USB endpoints for given device")?;
 
+        // Switch configuration only when needed, resetting it disturbs other users
+        let active_config = handle
+            .active_configuration()
+            .context("Failed to get active configuration")?;
+        if active_config != endpoints.config_value {
+            handle
+                .set_active_configuration(endpoints.config_value)
+                .context("Failed to set active configuration")?;
+        }
+
+        // Claim the device by claiming the interface holding the endpoints
+        Self::claim_interface(&handle, &usb_device, &endpoints, options)
+            .context("Failed to claim interface for given USB device")?;
+
+        if endpoints.setting_number != 0 {
+            handle
+                .set_alternate_setting(endpoints.interface_number, endpoints.setting_number)
+                .context("Failed to select alternate setting")?;
+        }
+
         let mut device = Self {
             handle,
             endpoints,
             timeout: Duration::from_secs(5),
+            write_delay: options.write_delay,
+            leftover: RefCell::new(Vec::new()),
         };
 
         // Send initialization control transfers
@@ -68,39 +137,68 @@ impl UsbDevice {
 
     /// Write raw data to device
     pub fn write(&self, data: &[u8]) -> Result<usize> {
-        // Transfer type ensure in endpoint getting stage - Interrupt or Bulk
-        let transferred = if self.endpoints.write_type == TransferType::Interrupt as u8 {
-            self.handle
-                .write_interrupt(self.endpoints.write_addr, data, self.timeout)
-        } else {
-            self.handle
-                .write_bulk(self.endpoints.write_addr, data, self.timeout)
+        let transferred = self
+            .write_transfer(data)
+            .context("Failed to write data to device")?;
+
+        // Terminate with a zero-length packet when the transfer is an exact multiple
+        // of the max packet size, otherwise the device keeps waiting for more data
+        if !data.is_empty()
+            && data
+                .len()
+                .is_multiple_of(self.endpoints.write_max_packet_size)
+        {
+            self.write_transfer(&[])
+                .context("Failed to write zero-length packet to device")?;
         }
-        .context("Failed to write data to device")?;
 
         // Wait some time for the multimeter to process
-        std::thread::sleep(Duration::from_millis(20));
+        if !self.write_delay.is_zero() {
+            std::thread::sleep(self.write_delay);
+        }
 
         Ok(transferred)
     }
 
     /// Read raw data from device
+    ///
+    /// Data received beyond the end of `buffer` is kept and returned by the next read.
     pub fn read(&self, buffer: &mut [u8]) -> Result<usize> {
-        // Transfer type ensure in endpoint getting stage - Interrupt or Bulk
-        let transferred = if self.endpoints.read_type == rusb::TransferType::Interrupt as u8 {
-            self.handle
-                .read_interrupt(self.endpoints.read_addr, buffer, self.timeout)
-        } else {
-            self.handle
-                .read_bulk(self.endpoints.read_addr, buffer, self.timeout)
+        // Hand out data left over from the previous transfer first
+        let mut leftover = self.leftover.borrow_mut();
+        if !leftover.is_empty() {
+            let copied = leftover.len().min(buffer.len());
+            buffer[..copied].copy_from_slice(&leftover[..copied]);
+            leftover.drain(..copied);
+            return Ok(copied);
         }
-        .context("Failed to read data from device")?;
 
-        Ok(transferred)
+        let max_packet_size = self.endpoints.read_max_packet_size;
+
+        // A buffer that is not a multiple of the max packet size may overflow when the
+        // device sends a full packet, so read into an aligned buffer if needed
+        if buffer.len().is_multiple_of(max_packet_size) {
+            return self
+                .read_transfer(buffer)
+                .context("Failed to read data from device");
+        }
+
+        let aligned_len = buffer.len().div_ceil(max_packet_size) * max_packet_size;
+        let mut aligned = vec![0u8; aligned_len];
+        let transferred = self
+            .read_transfer(&mut aligned)
+            .context("Failed to read data from device")?;
+
+        let copied = transferred.min(buffer.len());
+        buffer[..copied].copy_from_slice(&aligned[..copied]);
+        leftover.extend_from_slice(&aligned[copied..transferred]);
+
+        Ok(copied)
     }
 
     /// Clear halt on both endpoints
     pub fn clear_halt(&self) -> Result<()> {
+        self.leftover.borrow_mut().clear();
         self.handle
             .clear_halt(self.endpoints.read_addr)
             .context("Failed to clear read endpoint halt")?;
@@ -111,7 +209,6 @@ impl UsbDevice {
     }
 
     /// Read status byte via control transfer
-    #[allow(unused)]
     pub fn read_status(&self) -> Result<u8> {
         let mut status = [0u8; 1];
         let transferred = self
@@ -133,29 +230,120 @@ impl UsbDevice {
         Ok(status[0])
     }
 
+    /// Internal method: Claim the interface, retrying while busy within the claim timeout
+    fn claim_interface(
+        handle: &DeviceHandle<RUsbContext>,
+        device: &Device<RUsbContext>,
+        endpoints: &UsbEndpoints,
+        options: &OpenOptions,
+    ) -> Result<()> {
+        let interface_number = endpoints.interface_number;
+
+        // Kernel driver query is not supported on every platform, treat that as detached
+        let kernel_driver_active = matches!(
+            handle.kernel_driver_active(interface_number),
+            Result::Ok(true)
+        );
+
+        if options.detach_kernel_driver && kernel_driver_active {
+            handle
+                .detach_kernel_driver(interface_number)
+                .context("Failed to detach kernel driver")?;
+        }
+
+        let deadline = Instant::now() + options.claim_timeout;
+        loop {
+            match handle.claim_interface(interface_number) {
+                Result::Ok(()) => return Ok(()),
+                Err(rusb::Error::Busy) if Instant::now() < deadline => {
+                    std::thread::sleep(options.claim_retry_interval);
+                }
+                Err(rusb::Error::Busy) => {
+                    let holders = describe_interface_holders(
+                        handle,
+                        device,
+                        interface_number,
+                        endpoints.config_value,
+                    );
+                    return Err(anyhow::Error::new(rusb::Error::Busy).context(format!(
+                        "Interface {} is busy ({})",
+                        interface_number, holders
+                    )));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Internal method: Single write transfer on the write endpoint
+    fn write_transfer(&self, data: &[u8]) -> rusb::Result<usize> {
+        // Transfer type ensure in endpoint getting stage - Interrupt or Bulk
+        if self.endpoints.write_type == TransferType::Interrupt as u8 {
+            self.handle
+                .write_interrupt(self.endpoints.write_addr, data, self.timeout)
+        } else {
+            self.handle
+                .write_bulk(self.endpoints.write_addr, data, self.timeout)
+        }
+    }
+
+    /// Internal method: Single read transfer on the read endpoint
+    fn read_transfer(&self, buffer: &mut [u8]) -> rusb::Result<usize> {
+        // Transfer type ensure in endpoint getting stage - Interrupt or Bulk
+        if self.endpoints.read_type == TransferType::Interrupt as u8 {
+            self.handle
+                .read_interrupt(self.endpoints.read_addr, buffer, self.timeout)
+        } else {
+            self.handle
+                .read_bulk(self.endpoints.read_addr, buffer, self.timeout)
+        }
+    }
+
     /// Internal method: Get all endpoints
+    ///
+    /// Walks every configuration, interface and alternate setting, and picks the first
+    /// interface that exposes both a Bulk/Interrupt IN and OUT endpoint.
     fn get_endpoints(device: &Device<RUsbContext>) -> Result<UsbEndpoints> {
-        // ADCMT 7351A/E+03 only have one config descriptor
-        let config_descriptor = device
-            .config_descriptor(0)
-            .context("Failed to get config descriptor")?;
-
-        let interface = config_descriptor
-            .interfaces()
-            .next()
-            .ok_or_else(|| anyhow!("No interfaces found"))?;
+        let device_descriptor = device
+            .device_descriptor()
+            .context("Failed to get device descriptor")?;
+
+        for config_index in 0..device_descriptor.num_configurations() {
+            let config_descriptor = device
+                .config_descriptor(config_index)
+                .with_context(|| format!("Failed to get config descriptor {}", config_index))?;
+
+            for interface in config_descriptor.interfaces() {
+                for interface_descriptor in interface.descriptors() {
+                    if let Some(endpoints) =
+                        Self::find_endpoint_pair(config_descriptor.number(), &interface_descriptor)
+                    {
+                        return Ok(endpoints);
+                    }
+                }
+            }
+        }
 
-        let interface_descriptor = interface
-            .descriptors()
-            .next()
-            .ok_or_else(|| anyhow!("No interface descriptors found"))?;
+        Err(anyhow!(
+            "No interface with suitable READ and WRITE endpoints found"
+        ))
+    }
 
+    /// Internal method: Find a READ/WRITE endpoint pair within an interface setting
+    ///
+    /// Settings whose endpoints have a zero max packet size (e.g. a zero-bandwidth alternate
+    /// setting 0) are skipped, so the search moves on to the next setting.
+    fn find_endpoint_pair(
+        config_value: u8,
+        interface_descriptor: &InterfaceDescriptor,
+    ) -> Option<UsbEndpoints> {
         let (mut read_endpoint, mut write_endpoint) = (None, None);
 
         for endpoint_descriptor in interface_descriptor.endpoint_descriptors() {
             let address = endpoint_descriptor.address();
             let direction = address & rusb::constants::LIBUSB_ENDPOINT_DIR_MASK;
             let endpoint_type = endpoint_descriptor.transfer_type();
+            let max_packet_size = endpoint_descriptor.max_packet_size();
 
             // Only take care of Bulk and Interrupt type
             if !matches!(endpoint_type, TransferType::Bulk | TransferType::Interrupt) {
@@ -164,12 +352,10 @@ impl UsbDevice {
 
             if direction == rusb::constants::LIBUSB_ENDPOINT_IN {
                 if read_endpoint.is_none() {
-                    read_endpoint = Some((address, endpoint_type));
-                }
-            } else {
-                if write_endpoint.is_none() {
-                    write_endpoint = Some((address, endpoint_type));
+                    read_endpoint = Some((address, endpoint_type, max_packet_size));
                 }
+            } else if write_endpoint.is_none() {
+                write_endpoint = Some((address, endpoint_type, max_packet_size));
             }
 
             if read_endpoint.is_some() && write_endpoint.is_some() {
@@ -177,25 +363,32 @@ impl UsbDevice {
             }
         }
 
-        let (read_addr, raw_read_type) =
-            read_endpoint.ok_or_else(|| anyhow!("No suitable READ endpoint found"))?;
-
-        let (write_addr, raw_write_type) =
-            write_endpoint.ok_or_else(|| anyhow!("No suitable WRITE endpoint found"))?;
+        let (
+            Some((read_addr, raw_read_type, raw_read_max_packet_size)),
+            Some((write_addr, raw_write_type, raw_write_max_packet_size)),
+        ) = (read_endpoint, write_endpoint)
+        else {
+            return None;
+        };
 
-        let read_type: u8 = (raw_read_type as u8)
-            .try_into()
-            .context("Read TransferType failed to cast to u8")?;
+        // Bits 10..0 of wMaxPacketSize hold the packet size, upper bits are for high-bandwidth
+        let read_max_packet_size = (raw_read_max_packet_size & 0x07FF) as usize;
+        let write_max_packet_size = (raw_write_max_packet_size & 0x07FF) as usize;
 
-        let write_type: u8 = (raw_write_type as u8)
-            .try_into()
-            .context("Write TransferType failed to cast to u8")?;
+        if read_max_packet_size == 0 || write_max_packet_size == 0 {
+            return None;
+        }
 
-        Ok(UsbEndpoints {
+        Some(UsbEndpoints {
+            config_value,
+            interface_number: interface_descriptor.interface_number(),
+            setting_number: interface_descriptor.setting_number(),
             read_addr,
-            read_type,
+            read_type: raw_read_type as u8,
+            read_max_packet_size,
             write_addr,
-            write_type,
+            write_type: raw_write_type as u8,
+            write_max_packet_size,
         })
     }
 
@@ -231,9 +424,49 @@ impl UsbDevice {
     }
 }
 
+impl Transport for UsbDevice {
+    fn write(&self, data: &[u8]) -> Result<usize> {
+        UsbDevice::write(self, data)
+    }
+
+    fn read(&self, buffer: &mut [u8]) -> Result<usize> {
+        UsbDevice::read(self, buffer)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) {
+        UsbDevice::set_timeout(self, timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        UsbDevice::timeout(self)
+    }
+
+    fn clear_halt(&self) -> Result<()> {
+        UsbDevice::clear_halt(self)
+    }
+
+    fn read_status(&self) -> Result<u8> {
+        UsbDevice::read_status(self)
+    }
+
+    fn set_write_delay(&mut self, delay: Duration) {
+        self.write_delay = delay
+    }
+
+    fn write_delay(&self) -> Duration {
+        self.write_delay
+    }
+
+    fn max_packet_size(&self) -> usize {
+        self.endpoints.read_max_packet_size
+    }
+}
+
 impl Drop for UsbDevice {
     fn drop(&mut self) {
         // Release held resource
-        let _ = self.handle.release_interface(0);
+        let _ = self
+            .handle
+            .release_interface(self.endpoints.interface_number);
     }
 }
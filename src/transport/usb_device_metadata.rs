@@ -3,6 +3,8 @@
 use anyhow::{Context, Ok, Result};
 use rusb::{Context as RUsbContext, Device, DeviceDescriptor};
 
+use crate::transport::permission_error::open_error;
+
 #[derive(Debug, Clone)]
 pub struct UsbDeviceMetadata {
     pub device: Device<RUsbContext>,
@@ -19,6 +21,7 @@ impl UsbDeviceMetadata {
     ) -> Result<Self> {
         let handle = device
             .open()
+            .map_err(|e| open_error(device, e))
             .context("Failed to open device for descriptor read")?;
 
         let serial_number = descriptor